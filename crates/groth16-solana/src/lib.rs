@@ -30,10 +30,28 @@ use solana_program::alt_bn128::prelude::{
     alt_bn128_addition, alt_bn128_multiplication, alt_bn128_pairing, AltBn128Error,
     ALT_BN128_FIELD_SIZE,
 };
+use solana_program::keccak;
+
+#[cfg(feature = "native")]
+mod native;
+#[cfg(feature = "std")]
+pub mod snarkjs;
 
 const G1_SIZE: usize = ALT_BN128_FIELD_SIZE * 2;
 const G2_SIZE: usize = ALT_BN128_FIELD_SIZE * 4;
 
+/// BN254 base field modulus (big-endian)
+const FQ_MODULUS: F = [
+    48, 100, 78, 114, 225, 49, 160, 41, 184, 80, 69, 182, 129, 129, 88, 93, 151, 129, 106, 145,
+    104, 113, 202, 141, 60, 32, 140, 22, 216, 124, 253, 71,
+];
+
+/// BN254 scalar field modulus (big-endian)
+const FR_MODULUS: F = [
+    48, 100, 78, 114, 225, 49, 160, 41, 184, 80, 69, 182, 129, 129, 88, 93, 40, 51, 232, 72, 121,
+    185, 112, 145, 67, 225, 245, 147, 240, 0, 0, 1,
+];
+
 pub type F = [u8; ALT_BN128_FIELD_SIZE];
 pub type G1 = [u8; G1_SIZE];
 pub type G2 = [u8; G2_SIZE];
@@ -44,6 +62,9 @@ pub enum Groth16Error {
     InvalidProof,
     InvalidPublicInputs,
     VerificationFailed,
+    PublicInputOutOfRange,
+    PointNotOnCurve,
+    PointNotInSubgroup,
 }
 
 #[derive(PartialEq, Eq, Debug)]
@@ -82,6 +103,33 @@ impl Proof {
             Ok(())
         }
     }
+
+    /// Checks that `A` and `C` are on the curve and `B` is in the prime order subgroup.
+    /// The BN254 G1 group has cofactor 1, so every G1 point on the curve is in the subgroup.
+    pub fn validate_points(&self) -> Result<(), Groth16Error> {
+        validate_g1(&self.a)?;
+        validate_g2(&self.b)?;
+        validate_g1(&self.c)
+    }
+}
+
+impl<'a> VK<'a> {
+    /// Checks that all points of the key are well-formed, see [Proof::validate_points]
+    pub fn validate_points(&self) -> Result<(), Groth16Error> {
+        validate_g1(&self.alpha)?;
+        for point in [&self.beta, &self.gamma, &self.delta] {
+            validate_g2(point)?;
+        }
+        self.ic.iter().try_for_each(validate_g1)
+    }
+}
+
+/// Checks that all public inputs are canonical scalar field elements (less than `r`)
+pub fn validate_public_inputs(public_inputs: &[F]) -> Result<(), Groth16Error> {
+    if public_inputs.iter().any(|i| *i >= FR_MODULUS) {
+        return Err(Groth16Error::PublicInputOutOfRange);
+    }
+    Ok(())
 }
 
 #[derive(PartialEq, Eq, Debug)]
@@ -106,25 +154,33 @@ impl<'a> Groth16Verifier<'a> {
         })
     }
 
-    pub fn verify(&self) -> Result<bool, Groth16Error> {
-        let mut acc = self.vk.ic[0];
-        let mut input = Vec::with_capacity(G1_SIZE + ALT_BN128_FIELD_SIZE);
-
-        for (i, ic) in self.public_inputs.iter().zip(self.vk.ic.iter().skip(1)) {
-            input.clear();
-            input.extend_from_slice(ic);
-            input.extend_from_slice(i);
-
-            let mut mul_res =
-                alt_bn128_multiplication(&input).map_err(Groth16Error::AltBn128Error)?;
+    /// Same as [Self::new], but also rejects public inputs that are out of the scalar field
+    /// (e.g. `x + r`, which the pairing check accepts as `x`) and malformed proof points.
+    pub fn new_strict(
+        proof: &'a Proof,
+        public_inputs: &'a [F],
+        vk: &'a VK,
+    ) -> Result<Self, Groth16Error> {
+        let verifier = Self::new(proof, public_inputs, vk)?;
+        validate_public_inputs(public_inputs)?;
+        proof.validate_points()?;
+        Ok(verifier)
+    }
 
-            mul_res.extend_from_slice(&acc);
+    fn prepare_inputs(&self) -> Result<G1, Groth16Error> {
+        prepare_inputs(self.vk.ic, self.public_inputs)
+    }
 
-            acc = alt_bn128_addition(&mul_res)
-                .map_err(Groth16Error::AltBn128Error)?
-                .try_into()
-                .expect("conversion failed");
-        }
+    /// Checks `e(-A, B) * e(acc, gamma) * e(C, delta) * e(alpha, beta) == 1`,
+    /// where `acc` is the linear combination of `ic` with the public inputs.
+    ///
+    /// The key is used as is: the only negation (`A`) is done by the client when the proof
+    /// is imported (see `snarkjs::parse_proof`). The `alt_bn128` syscall only checks that a product
+    /// of pairings equals one and cannot take a precomputed `e(alpha, beta)`,
+    /// so a single proof always takes 4 pairs; use [Groth16BatchVerifier]
+    /// to amortize them across several proofs.
+    pub fn verify(&self) -> Result<bool, Groth16Error> {
+        let acc = self.prepare_inputs()?;
 
         let mut input = Vec::with_capacity(G1_SIZE * 4 + G2_SIZE * 4);
         input.extend_from_slice(self.proof.a.as_slice());
@@ -136,13 +192,229 @@ impl<'a> Groth16Verifier<'a> {
         input.extend_from_slice(self.vk.alpha.as_slice());
         input.extend_from_slice(self.vk.beta.as_slice());
 
-
         let res = alt_bn128_pairing(input.as_slice()).map_err(Groth16Error::AltBn128Error)?;
-        
+
+        if res[31] != 1 {
+            return Err(Groth16Error::VerificationFailed);
+        }
+
+        Ok(true)
+    }
+}
+
+/// Verifies several proofs, possibly across different verification keys,
+/// with a single pairing call.
+///
+/// Each proof equation is scaled by a random 128-bit factor `r_i` derived from the hash
+/// of all proofs, public inputs and verification keys, so a prover cannot choose proofs
+/// that cancel each other out. Terms sharing a verification key are aggregated,
+/// which results in `n + 3 * k` pairs for `n` proofs over `k` distinct keys:
+///
+/// `prod e(r_i * -A_i, B_i) * prod_vk e(sum r_i * acc_i, gamma) * e(sum r_i * C_i, delta) * e(sum r_i * alpha, beta) == 1`
+#[derive(Debug, Default)]
+pub struct Groth16BatchVerifier<'a> {
+    verifiers: Vec<Groth16Verifier<'a>>,
+}
+
+impl<'a> Groth16BatchVerifier<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a proof to the batch, see [Groth16Verifier::new]
+    pub fn push(
+        &mut self,
+        proof: &'a Proof,
+        public_inputs: &'a [F],
+        vk: &'a VK,
+    ) -> Result<(), Groth16Error> {
+        self.verifiers
+            .push(Groth16Verifier::new(proof, public_inputs, vk)?);
+        Ok(())
+    }
+
+    /// Adds a proof to the batch, see [Groth16Verifier::new_strict]
+    pub fn push_strict(
+        &mut self,
+        proof: &'a Proof,
+        public_inputs: &'a [F],
+        vk: &'a VK,
+    ) -> Result<(), Groth16Error> {
+        self.verifiers
+            .push(Groth16Verifier::new_strict(proof, public_inputs, vk)?);
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.verifiers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.verifiers.is_empty()
+    }
+
+    /// Verifies all proofs of the batch, an empty batch is rejected
+    pub fn verify(&self) -> Result<bool, Groth16Error> {
+        if self.verifiers.is_empty() {
+            return Err(Groth16Error::InvalidProof);
+        }
+
+        let seed = self.transcript_hash();
+
+        // (vk, sum r_i * acc_i, sum r_i * C_i, sum r_i)
+        let mut groups: Vec<(&VK, G1, G1, F)> = Vec::new();
+        let mut input = Vec::with_capacity((G1_SIZE + G2_SIZE) * (self.verifiers.len() + 3));
+
+        for (i, verifier) in self.verifiers.iter().enumerate() {
+            let r = batch_scalar(&seed, i);
+            let acc = g1_mul(&verifier.prepare_inputs()?, &r)?;
+            let c = g1_mul(&verifier.proof.c, &r)?;
+
+            input.extend_from_slice(&g1_mul(&verifier.proof.a, &r)?);
+            input.extend_from_slice(&verifier.proof.b);
+
+            match groups.iter_mut().find(|g| *g.0 == *verifier.vk) {
+                Some(group) => {
+                    group.1 = g1_add(&group.1, &acc)?;
+                    group.2 = g1_add(&group.2, &c)?;
+                    add_scalars(&mut group.3, &r);
+                }
+                None => groups.push((verifier.vk, acc, c, r)),
+            }
+        }
+
+        for (vk, acc, c, r) in groups {
+            input.extend_from_slice(&acc);
+            input.extend_from_slice(&vk.gamma);
+            input.extend_from_slice(&c);
+            input.extend_from_slice(&vk.delta);
+            input.extend_from_slice(&g1_mul(&vk.alpha, &r)?);
+            input.extend_from_slice(&vk.beta);
+        }
+
+        let res = alt_bn128_pairing(&input).map_err(Groth16Error::AltBn128Error)?;
+
         if res[31] != 1 {
             return Err(Groth16Error::VerificationFailed);
         }
 
         Ok(true)
     }
+
+    /// Hashes all proofs, public inputs and verification keys of the batch
+    fn transcript_hash(&self) -> keccak::Hash {
+        let mut vals: Vec<&[u8]> = Vec::new();
+        for verifier in &self.verifiers {
+            let (proof, vk) = (verifier.proof, verifier.vk);
+            vals.extend([&proof.a[..], &proof.b, &proof.c]);
+            vals.extend(verifier.public_inputs.iter().map(|i| &i[..]));
+            vals.extend([&vk.alpha[..], &vk.beta, &vk.gamma, &vk.delta]);
+            vals.extend(vk.ic.iter().map(|ic| &ic[..]));
+        }
+        keccak::hashv(&vals)
+    }
+}
+
+/// Derives a non-zero 128-bit big-endian scalar for the `index` proof of the batch
+fn batch_scalar(seed: &keccak::Hash, index: usize) -> F {
+    let hash = keccak::hashv(&[seed.as_ref(), &(index as u64).to_le_bytes()]);
+    let mut scalar = [0u8; ALT_BN128_FIELD_SIZE];
+    scalar[16..].copy_from_slice(&hash.as_ref()[..16]);
+    scalar[31] |= 1;
+    scalar
+}
+
+/// Adds two big-endian scalars, the sum of 128-bit scalars never overflows
+fn add_scalars(a: &mut F, b: &F) {
+    let mut carry = 0u16;
+    for (x, y) in a.iter_mut().zip(b.iter()).rev() {
+        let sum = *x as u16 + *y as u16 + carry;
+        *x = sum as u8;
+        carry = sum >> 8;
+    }
+}
+
+/// Computes the linear combination of `ic` with the public inputs
+fn prepare_inputs(ic: &[G1], public_inputs: &[F]) -> Result<G1, Groth16Error> {
+    let mut acc = ic[0];
+
+    for (i, ic) in public_inputs.iter().zip(ic.iter().skip(1)) {
+        acc = g1_add(&g1_mul(ic, i)?, &acc)?;
+    }
+
+    Ok(acc)
+}
+
+/// Checks that the G1 point is canonical and on the curve
+fn validate_g1(point: &G1) -> Result<(), Groth16Error> {
+    if !is_canonical_point(point) {
+        return Err(Groth16Error::PointNotOnCurve);
+    }
+    alt_bn128_addition(point).map_err(|_| Groth16Error::PointNotOnCurve)?;
+    Ok(())
+}
+
+/// Checks that the G2 point is canonical and in the prime order subgroup
+fn validate_g2(point: &G2) -> Result<(), Groth16Error> {
+    if !is_canonical_point(point) {
+        return Err(Groth16Error::PointNotOnCurve);
+    }
+    // the syscall validates the G2 points of the pairing, `e(0, B)` is always one
+    let mut input = [0u8; G1_SIZE + G2_SIZE];
+    input[G1_SIZE..].copy_from_slice(point);
+    alt_bn128_pairing(&input).map_err(|_| Groth16Error::PointNotInSubgroup)?;
+    Ok(())
+}
+
+/// Checks that all coordinates of the point are less than the base field modulus
+fn is_canonical_point(point: &[u8]) -> bool {
+    point
+        .chunks(ALT_BN128_FIELD_SIZE)
+        .all(|coord| coord < &FQ_MODULUS[..])
+}
+
+/// Negates a base field element, zero is kept as is
+#[cfg(feature = "std")]
+fn fq_neg(value: &[u8]) -> F {
+    let mut res = [0u8; ALT_BN128_FIELD_SIZE];
+    if value.iter().all(|b| *b == 0) {
+        return res;
+    }
+    let mut borrow = 0i16;
+    for i in (0..ALT_BN128_FIELD_SIZE).rev() {
+        let diff = FQ_MODULUS[i] as i16 - value[i] as i16 - borrow;
+        res[i] = diff.rem_euclid(256) as u8;
+        borrow = (diff < 0) as i16;
+    }
+    res
+}
+
+/// Negates a G1 point `(x, y)` as `(x, -y)`
+#[cfg(feature = "std")]
+fn g1_neg(point: &G1) -> G1 {
+    let mut res = *point;
+    res[ALT_BN128_FIELD_SIZE..].copy_from_slice(&fq_neg(&point[ALT_BN128_FIELD_SIZE..]));
+    res
+}
+
+fn g1_mul(point: &G1, scalar: &F) -> Result<G1, Groth16Error> {
+    let mut input = [0u8; G1_SIZE + ALT_BN128_FIELD_SIZE];
+    input[..G1_SIZE].copy_from_slice(point);
+    input[G1_SIZE..].copy_from_slice(scalar);
+
+    alt_bn128_multiplication(&input)
+        .map_err(Groth16Error::AltBn128Error)?
+        .try_into()
+        .map_err(|v| Groth16Error::AltBn128Error(AltBn128Error::TryIntoVecError(v)))
+}
+
+fn g1_add(a: &G1, b: &G1) -> Result<G1, Groth16Error> {
+    let mut input = [0u8; G1_SIZE * 2];
+    input[..G1_SIZE].copy_from_slice(a);
+    input[G1_SIZE..].copy_from_slice(b);
+
+    alt_bn128_addition(&input)
+        .map_err(Groth16Error::AltBn128Error)?
+        .try_into()
+        .map_err(|v| Groth16Error::AltBn128Error(AltBn128Error::TryIntoVecError(v)))
 }
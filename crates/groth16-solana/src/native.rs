@@ -0,0 +1,106 @@
+/*
+ * This file is part of Albus code.
+ *
+ * Copyright (c) 2023, mFactory GmbH
+ *
+ * Albus is free software: you can redistribute it
+ * and/or modify it under the terms of the GNU Affero General Public License
+ * as published by the Free Software Foundation, either version 3
+ * of the License, or (at your option) any later version.
+ *
+ * Albus is distributed in the hope that it
+ * will be useful, but WITHOUT ANY WARRANTY; without even the implied
+ * warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.
+ * If not, see <https://www.gnu.org/licenses/agpl-3.0.html>.
+ *
+ * You can be released from the requirements of the Affero GNU General Public License
+ * by purchasing a commercial license. The purchase of such a license is
+ * mandatory as soon as you develop commercial activities using the
+ * Albus code without disclosing the source code of
+ * your own applications.
+ *
+ * The developer of this program can be contacted at <info@albus.finance>.
+ */
+
+//! Pure-Rust BN254 backend for off-chain services (indexers, backends), which computes
+//! the same verdict as the `alt_bn128` syscalls used by [Groth16Verifier::verify].
+//! Points are decoded with the same rules as the syscalls: all-zero bytes are the point
+//! at infinity, coordinates must be canonical and points must be on the curve
+//! (and in the prime order subgroup for G2); scalars are not reduced.
+
+use crate::{Groth16Error, Groth16Verifier, F, G1, G2};
+use ark_bn254::{Bn254, G1Affine, G1Projective, G2Affine};
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_ff::{BigInteger256, One};
+use ark_serialize::{CanonicalDeserialize, Compress, Validate};
+use solana_program::alt_bn128::AltBn128Error;
+
+impl<'a> Groth16Verifier<'a> {
+    /// Same as [Self::verify], computed without the `alt_bn128` syscalls
+    pub fn verify_native(&self) -> Result<bool, Groth16Error> {
+        let mut acc = G1Projective::from(decode_g1(&self.vk.ic[0])?);
+        for (i, ic) in self.public_inputs.iter().zip(self.vk.ic.iter().skip(1)) {
+            acc += decode_g1(ic)?.mul_bigint(decode_scalar(i)?);
+        }
+
+        let res = Bn254::multi_pairing(
+            [
+                decode_g1(&self.proof.a)?,
+                acc.into_affine(),
+                decode_g1(&self.proof.c)?,
+                decode_g1(&self.vk.alpha)?,
+            ],
+            [
+                decode_g2(&self.proof.b)?,
+                decode_g2(&self.vk.gamma)?,
+                decode_g2(&self.vk.delta)?,
+                decode_g2(&self.vk.beta)?,
+            ],
+        );
+
+        if !res.0.is_one() {
+            return Err(Groth16Error::VerificationFailed);
+        }
+
+        Ok(true)
+    }
+}
+
+fn decode_g1(point: &G1) -> Result<G1Affine, Groth16Error> {
+    if point.iter().all(|b| *b == 0) {
+        return Ok(G1Affine::zero());
+    }
+    // big-endian `x || y` to little-endian, plus the flags byte
+    let mut bytes = [0u8; 65];
+    for (i, chunk) in point.chunks(32).enumerate() {
+        bytes[i * 32..(i + 1) * 32].copy_from_slice(chunk);
+        bytes[i * 32..(i + 1) * 32].reverse();
+    }
+    G1Affine::deserialize_with_mode(&bytes[..], Compress::No, Validate::Yes)
+        .map_err(|_| Groth16Error::AltBn128Error(AltBn128Error::InvalidInputData))
+}
+
+fn decode_g2(point: &G2) -> Result<G2Affine, Groth16Error> {
+    if point.iter().all(|b| *b == 0) {
+        return Ok(G2Affine::zero());
+    }
+    // big-endian `x_c1 || x_c0 || y_c1 || y_c0` to little-endian `x_c0 || x_c1 || y_c0 || y_c1`
+    let mut bytes = [0u8; 129];
+    for (i, chunk) in point.chunks(64).enumerate() {
+        bytes[i * 64..(i + 1) * 64].copy_from_slice(chunk);
+        bytes[i * 64..(i + 1) * 64].reverse();
+    }
+    G2Affine::deserialize_with_mode(&bytes[..], Compress::No, Validate::Yes)
+        .map_err(|_| Groth16Error::AltBn128Error(AltBn128Error::InvalidInputData))
+}
+
+fn decode_scalar(scalar: &F) -> Result<BigInteger256, Groth16Error> {
+    let mut bytes = *scalar;
+    bytes.reverse();
+    BigInteger256::deserialize_uncompressed_unchecked(&bytes[..])
+        .map_err(|_| Groth16Error::AltBn128Error(AltBn128Error::InvalidInputData))
+}
@@ -0,0 +1,282 @@
+/*
+ * This file is part of Albus code.
+ *
+ * Copyright (c) 2023, mFactory GmbH
+ *
+ * Albus is free software: you can redistribute it
+ * and/or modify it under the terms of the GNU Affero General Public License
+ * as published by the Free Software Foundation, either version 3
+ * of the License, or (at your option) any later version.
+ *
+ * Albus is distributed in the hope that it
+ * will be useful, but WITHOUT ANY WARRANTY; without even the implied
+ * warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.
+ * If not, see <https://www.gnu.org/licenses/agpl-3.0.html>.
+ *
+ * You can be released from the requirements of the Affero GNU General Public License
+ * by purchasing a commercial license. The purchase of such a license is
+ * mandatory as soon as you develop commercial activities using the
+ * Albus code without disclosing the source code of
+ * your own applications.
+ *
+ * The developer of this program can be contacted at <info@albus.finance>.
+ */
+
+//! Conversion between the snarkjs JSON files (`proof.json`, `public.json`,
+//! `verification_key.json`) and the big-endian byte layout used by the `alt_bn128` syscalls.
+//!
+//! Field elements are decimal strings in snarkjs. G2 coordinates are `[c0, c1]` in snarkjs
+//! and `c1 || c0` in the syscalls. The proof `A` point is negated on import (and restored
+//! on export), as expected by [crate::Groth16Verifier].
+
+use crate::{g1_neg, Proof, F, G1, G1_SIZE, G2, G2_SIZE, VK};
+use serde::{Deserialize, Serialize};
+use solana_program::alt_bn128::prelude::ALT_BN128_FIELD_SIZE;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SnarkjsError {
+    InvalidJson(String),
+    InvalidNumber,
+    InvalidPoint,
+}
+
+/// Verification key with owned `ic` points, see [VerificationKey::as_vk]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VerificationKey {
+    pub alpha: G1,
+    pub beta: G2,
+    pub gamma: G2,
+    pub delta: G2,
+    pub ic: Vec<G1>,
+}
+
+impl VerificationKey {
+    pub fn as_vk(&self) -> VK<'_> {
+        VK {
+            alpha: self.alpha,
+            beta: self.beta,
+            gamma: self.gamma,
+            delta: self.delta,
+            ic: &self.ic,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct ProofJson {
+    pi_a: Vec<String>,
+    pi_b: Vec<Vec<String>>,
+    pi_c: Vec<String>,
+    #[serde(default = "default_protocol")]
+    protocol: String,
+    #[serde(default = "default_curve")]
+    curve: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct VerificationKeyJson {
+    #[serde(default = "default_protocol")]
+    protocol: String,
+    #[serde(default = "default_curve")]
+    curve: String,
+    #[serde(rename = "nPublic")]
+    n_public: usize,
+    vk_alpha_1: Vec<String>,
+    vk_beta_2: Vec<Vec<String>>,
+    vk_gamma_2: Vec<Vec<String>>,
+    vk_delta_2: Vec<Vec<String>>,
+    #[serde(rename = "IC")]
+    ic: Vec<Vec<String>>,
+}
+
+fn default_protocol() -> String {
+    "groth16".into()
+}
+
+fn default_curve() -> String {
+    "bn128".into()
+}
+
+/// Parses the snarkjs `proof.json`, the `A` point is negated
+pub fn parse_proof(json: &str) -> Result<Proof, SnarkjsError> {
+    let proof: ProofJson = from_json(json)?;
+    Ok(Proof::new(
+        g1_neg(&parse_g1(&proof.pi_a)?),
+        parse_g2(&proof.pi_b)?,
+        parse_g1(&proof.pi_c)?,
+    ))
+}
+
+/// Parses the snarkjs `public.json`
+pub fn parse_public_inputs(json: &str) -> Result<Vec<F>, SnarkjsError> {
+    let inputs: Vec<String> = from_json(json)?;
+    inputs.iter().map(|i| parse_field(i)).collect()
+}
+
+/// Parses the snarkjs `verification_key.json`
+pub fn parse_verification_key(json: &str) -> Result<VerificationKey, SnarkjsError> {
+    let vk: VerificationKeyJson = from_json(json)?;
+    if vk.ic.len() != vk.n_public + 1 {
+        return Err(SnarkjsError::InvalidJson(
+            "`IC` length does not match `nPublic`".into(),
+        ));
+    }
+    Ok(VerificationKey {
+        alpha: parse_g1(&vk.vk_alpha_1)?,
+        beta: parse_g2(&vk.vk_beta_2)?,
+        gamma: parse_g2(&vk.vk_gamma_2)?,
+        delta: parse_g2(&vk.vk_delta_2)?,
+        ic: vk
+            .ic
+            .iter()
+            .map(|p| parse_g1(p))
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+/// Serializes the proof to the snarkjs `proof.json` format, the `A` point is negated back
+pub fn proof_to_json(proof: &Proof) -> String {
+    to_json(&ProofJson {
+        pi_a: format_g1(&g1_neg(&proof.a)),
+        pi_b: format_g2(&proof.b),
+        pi_c: format_g1(&proof.c),
+        protocol: default_protocol(),
+        curve: default_curve(),
+    })
+}
+
+/// Serializes the public inputs to the snarkjs `public.json` format
+pub fn public_inputs_to_json(public_inputs: &[F]) -> String {
+    to_json(&public_inputs.iter().map(format_field).collect::<Vec<_>>())
+}
+
+/// Serializes the verification key to the snarkjs `verification_key.json` format.
+/// The `vk_alphabeta_12` pairing is not included, snarkjs does not need it to verify.
+pub fn verification_key_to_json(vk: &VK) -> String {
+    to_json(&VerificationKeyJson {
+        protocol: default_protocol(),
+        curve: default_curve(),
+        n_public: vk.ic.len().saturating_sub(1),
+        vk_alpha_1: format_g1(&vk.alpha),
+        vk_beta_2: format_g2(&vk.beta),
+        vk_gamma_2: format_g2(&vk.gamma),
+        vk_delta_2: format_g2(&vk.delta),
+        ic: vk.ic.iter().map(format_g1).collect(),
+    })
+}
+
+fn from_json<'de, T: Deserialize<'de>>(json: &'de str) -> Result<T, SnarkjsError> {
+    serde_json::from_str(json).map_err(|e| SnarkjsError::InvalidJson(e.to_string()))
+}
+
+fn to_json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string_pretty(value).expect("serialization failed")
+}
+
+/// Parses a projective `[x, y, z]` G1 point, `z` must be `1` or `0` (point at infinity)
+fn parse_g1(point: &[String]) -> Result<G1, SnarkjsError> {
+    let mut res = [0u8; G1_SIZE];
+    match point {
+        [x, y, z] if z == "1" => {
+            res[..ALT_BN128_FIELD_SIZE].copy_from_slice(&parse_field(x)?);
+            res[ALT_BN128_FIELD_SIZE..].copy_from_slice(&parse_field(y)?);
+        }
+        [_, _, z] if z == "0" => {}
+        _ => return Err(SnarkjsError::InvalidPoint),
+    }
+    Ok(res)
+}
+
+/// Parses a projective `[[x0, x1], [y0, y1], [z0, z1]]` G2 point as `x1 || x0 || y1 || y0`
+fn parse_g2(point: &[Vec<String>]) -> Result<G2, SnarkjsError> {
+    let mut res = [0u8; G2_SIZE];
+    match point {
+        [x, y, z] if x.len() == 2 && y.len() == 2 && z == &["1", "0"] => {
+            let coords = [x, y].into_iter().flat_map(|c| c.iter().rev());
+            for (i, c) in coords.enumerate() {
+                res[i * ALT_BN128_FIELD_SIZE..(i + 1) * ALT_BN128_FIELD_SIZE]
+                    .copy_from_slice(&parse_field(c)?);
+            }
+        }
+        [_, _, z] if z.iter().all(|c| c == "0") => {}
+        _ => return Err(SnarkjsError::InvalidPoint),
+    }
+    Ok(res)
+}
+
+fn format_g1(point: &G1) -> Vec<String> {
+    if point.iter().all(|b| *b == 0) {
+        return vec!["0".into(), "1".into(), "0".into()];
+    }
+    let mut res: Vec<_> = point
+        .chunks(ALT_BN128_FIELD_SIZE)
+        .map(format_field)
+        .collect();
+    res.push("1".into());
+    res
+}
+
+fn format_g2(point: &G2) -> Vec<Vec<String>> {
+    if point.iter().all(|b| *b == 0) {
+        return vec![
+            vec!["0".into(), "0".into()],
+            vec!["1".into(), "0".into()],
+            vec!["0".into(), "0".into()],
+        ];
+    }
+    let mut res: Vec<Vec<String>> = point
+        .chunks(ALT_BN128_FIELD_SIZE * 2)
+        .map(|c| {
+            c.chunks(ALT_BN128_FIELD_SIZE)
+                .rev()
+                .map(format_field)
+                .collect()
+        })
+        .collect();
+    res.push(vec!["1".into(), "0".into()]);
+    res
+}
+
+/// Parses a decimal string as a 32-byte big-endian number
+fn parse_field(value: &str) -> Result<F, SnarkjsError> {
+    if value.is_empty() {
+        return Err(SnarkjsError::InvalidNumber);
+    }
+    let mut res = [0u8; ALT_BN128_FIELD_SIZE];
+    for c in value.chars() {
+        let mut carry = c.to_digit(10).ok_or(SnarkjsError::InvalidNumber)?;
+        for b in res.iter_mut().rev() {
+            let v = *b as u32 * 10 + carry;
+            *b = v as u8;
+            carry = v >> 8;
+        }
+        if carry != 0 {
+            return Err(SnarkjsError::InvalidNumber);
+        }
+    }
+    Ok(res)
+}
+
+/// Formats a big-endian number as a decimal string
+fn format_field(value: impl AsRef<[u8]>) -> String {
+    let mut n = value.as_ref().to_vec();
+    let mut digits = Vec::new();
+    loop {
+        let mut rem = 0u32;
+        for b in n.iter_mut() {
+            let v = (rem << 8) | *b as u32;
+            *b = (v / 10) as u8;
+            rem = v % 10;
+        }
+        digits.push(b'0' + rem as u8);
+        if n.iter().all(|b| *b == 0) {
+            break;
+        }
+    }
+    digits.reverse();
+    String::from_utf8(digits).expect("invalid digits")
+}
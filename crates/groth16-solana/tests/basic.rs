@@ -1,8 +1,11 @@
+use ark_ec::AffineRepr;
+use ark_ff::{BigInteger, Field, PrimeField};
 use ark_serialize::CanonicalDeserialize;
 use ark_serialize::CanonicalSerialize;
 use ark_serialize::Compress;
 use ark_serialize::Validate;
-use groth16_solana::{Groth16Verifier, Proof, VK};
+use groth16_solana::{Groth16BatchVerifier, Groth16Error, Groth16Verifier, Proof, VK};
+use solana_program::alt_bn128::prelude::alt_bn128_multiplication;
 use solana_program::alt_bn128::AltBn128Error;
 
 #[global_allocator]
@@ -48,6 +51,230 @@ fn test_verify() {
     println!("Elapsed time: {:?}", elapsed_time);
 }
 
+#[cfg(feature = "std")]
+#[test]
+fn test_snarkjs() {
+    use groth16_solana::snarkjs;
+
+    let (vk, public_inputs, proof) = test_data();
+    let decimal = |bytes: &[u8]| ark_bn254::Fq::from_be_bytes_mod_order(bytes).to_string();
+
+    let json = snarkjs::proof_to_json(&Proof::new(
+        alt_bn128_g1_neg(&proof[..64]).unwrap().try_into().unwrap(),
+        proof[64..192].try_into().unwrap(),
+        proof[192..256].try_into().unwrap(),
+    ));
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["pi_a"][0], decimal(&proof[..32]));
+    assert_eq!(value["pi_a"][1], decimal(&proof[32..64]));
+    assert_eq!(value["pi_a"][2], "1");
+    assert_eq!(value["pi_b"][0][0], decimal(&proof[96..128]));
+    assert_eq!(value["pi_b"][0][1], decimal(&proof[64..96]));
+    assert_eq!(value["pi_b"][1][0], decimal(&proof[160..192]));
+    assert_eq!(value["pi_b"][2], serde_json::json!(["1", "0"]));
+    assert_eq!(value["pi_c"][0], decimal(&proof[192..224]));
+    let parsed_proof = snarkjs::parse_proof(&json).unwrap();
+    assert_eq!(snarkjs::proof_to_json(&parsed_proof), json);
+
+    let json = snarkjs::public_inputs_to_json(public_inputs);
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value[0], decimal(&public_inputs[0]));
+    assert_eq!(value[8], "18");
+    let parsed_inputs = snarkjs::parse_public_inputs(&json).unwrap();
+    assert_eq!(parsed_inputs, public_inputs);
+
+    let json = snarkjs::verification_key_to_json(&vk);
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["nPublic"], public_inputs.len());
+    assert_eq!(value["vk_alpha_1"][0], decimal(&vk.alpha[..32]));
+    assert_eq!(value["vk_gamma_2"][1][1], decimal(&vk.gamma[64..96]));
+    let parsed_vk = snarkjs::parse_verification_key(&json).unwrap();
+    let parsed_vk = parsed_vk.as_vk();
+    assert_eq!(parsed_vk, vk);
+
+    let verifier = Groth16Verifier::new_strict(&parsed_proof, &parsed_inputs, &parsed_vk).unwrap();
+    assert!(verifier.verify().unwrap());
+
+    assert!(snarkjs::parse_public_inputs(r#"["12a"]"#).is_err());
+    assert!(snarkjs::parse_proof(r#"{"pi_a": ["1", "2", "3"], "pi_b": [], "pi_c": []}"#).is_err());
+}
+
+#[test]
+fn test_strict_verify() {
+    let (vk, public_inputs, proof) = test_data();
+    let proof_a: [u8; 64] = alt_bn128_g1_neg(&proof[..64]).unwrap().try_into().unwrap();
+    let proof_b: [u8; 128] = proof[64..192].try_into().unwrap();
+    let proof_c: [u8; 64] = proof[192..256].try_into().unwrap();
+
+    let proof = Proof::new(proof_a, proof_b, proof_c);
+    let verifier = Groth16Verifier::new_strict(&proof, public_inputs, &vk).unwrap();
+    assert!(verifier.verify().unwrap());
+
+    // `x + r` is accepted as `x` by the pairing check
+    let modulus = ark_bn254::Fr::MODULUS.to_bytes_be();
+    let mut malleable_inputs = public_inputs.to_vec();
+    let mut carry = 0u16;
+    for (x, r) in malleable_inputs[8].iter_mut().zip(modulus).rev() {
+        let sum = *x as u16 + r as u16 + carry;
+        *x = sum as u8;
+        carry = sum >> 8;
+    }
+    let verifier = Groth16Verifier::new(&proof, &malleable_inputs, &vk).unwrap();
+    assert!(verifier.verify().unwrap());
+    assert_eq!(
+        Groth16Verifier::new_strict(&proof, &malleable_inputs, &vk),
+        Err(Groth16Error::PublicInputOutOfRange)
+    );
+
+    let mut invalid_a = proof_a;
+    invalid_a[63] ^= 1;
+    let proof = Proof::new(invalid_a, proof_b, proof_c);
+    assert_eq!(
+        Groth16Verifier::new_strict(&proof, public_inputs, &vk),
+        Err(Groth16Error::PointNotOnCurve)
+    );
+
+    let proof = Proof::new(proof_a, g2_not_in_subgroup(), proof_c);
+    assert_eq!(
+        Groth16Verifier::new_strict(&proof, public_inputs, &vk),
+        Err(Groth16Error::PointNotInSubgroup)
+    );
+}
+
+#[test]
+fn test_vk_validate_points() {
+    let (vk, _, _) = test_data();
+    assert_eq!(vk.validate_points(), Ok(()));
+
+    let mut ic = vk.ic.to_vec();
+    ic[1][63] ^= 1;
+    let invalid = VK { ic: &ic, ..vk };
+    assert_eq!(
+        invalid.validate_points(),
+        Err(Groth16Error::PointNotOnCurve)
+    );
+
+    let (vk, _, _) = test_data();
+    let invalid = VK {
+        gamma: g2_not_in_subgroup(),
+        ..vk
+    };
+    assert_eq!(
+        invalid.validate_points(),
+        Err(Groth16Error::PointNotInSubgroup)
+    );
+}
+
+#[cfg(feature = "native")]
+#[test]
+fn test_native_verify() {
+    let (vk, public_inputs, proof) = test_data();
+    let proof_a: [u8; 64] = alt_bn128_g1_neg(&proof[..64]).unwrap().try_into().unwrap();
+    let proof_b: [u8; 128] = proof[64..192].try_into().unwrap();
+    let proof_c: [u8; 64] = proof[192..256].try_into().unwrap();
+
+    let mut invalid_a = proof_a;
+    invalid_a[63] ^= 1;
+    let mut zero_c = proof_c;
+    zero_c.fill(0);
+
+    let mut invalid_inputs = public_inputs.to_vec();
+    invalid_inputs[0][31] ^= 1;
+    let mut large_inputs = public_inputs.to_vec();
+    large_inputs[8] = [0xff; 32];
+
+    let proofs = [
+        Proof::new(proof_a, proof_b, proof_c),
+        Proof::new(proof[..64].try_into().unwrap(), proof_b, proof_c),
+        Proof::new(invalid_a, proof_b, proof_c),
+        Proof::new(proof_a, g2_not_in_subgroup(), proof_c),
+        Proof::new(proof_a, proof_b, zero_c),
+    ];
+    let inputs = [public_inputs, &invalid_inputs[..], &large_inputs[..]];
+
+    for proof in &proofs {
+        for public_inputs in inputs {
+            let verifier = Groth16Verifier::new(proof, public_inputs, &vk).unwrap();
+            assert_eq!(verifier.verify_native(), verifier.verify());
+        }
+    }
+
+    let verifier = Groth16Verifier::new(&proofs[0], public_inputs, &vk).unwrap();
+    assert!(verifier.verify_native().unwrap());
+}
+
+/// Finds a point on the G2 curve which is not in the prime order subgroup
+fn g2_not_in_subgroup() -> [u8; 128] {
+    type G2 = ark_bn254::g2::G2Affine;
+    let mut x = ark_bn254::Fq2::ONE;
+    let point = loop {
+        if let Some(p) = G2::get_point_from_x_unchecked(x, true) {
+            if !p.is_in_correct_subgroup_assuming_on_curve() {
+                break p;
+            }
+        }
+        x += ark_bn254::Fq2::ONE;
+    };
+    let (x, y) = point.xy().unwrap();
+    [x.c1, x.c0, y.c1, y.c0]
+        .iter()
+        .flat_map(|c| c.into_bigint().to_bytes_be())
+        .collect::<Vec<_>>()
+        .try_into()
+        .unwrap()
+}
+
+#[test]
+fn test_batch_verify() {
+    let (vk, public_inputs, proof) = test_data();
+    let proof_a: [u8; 64] = alt_bn128_g1_neg(&proof[..64]).unwrap().try_into().unwrap();
+    let proof_b: [u8; 128] = proof[64..192].try_into().unwrap();
+    let proof_c: [u8; 64] = proof[192..256].try_into().unwrap();
+
+    // An equivalent key and proof, with `alpha`, `ic`, `A` and `C` scaled by the same factor
+    let mul = |point: &[u8]| -> [u8; 64] {
+        let mut scalar = [0u8; 32];
+        scalar[31] = 7;
+        alt_bn128_multiplication(&[point, &scalar].concat())
+            .unwrap()
+            .try_into()
+            .unwrap()
+    };
+    let ic = vk.ic.iter().map(|ic| mul(ic)).collect::<Vec<_>>();
+    let scaled_vk = VK {
+        alpha: mul(&vk.alpha),
+        beta: vk.beta,
+        gamma: vk.gamma,
+        delta: vk.delta,
+        ic: &ic,
+    };
+
+    let proof = Proof::new(proof_a, proof_b, proof_c);
+    let scaled_proof = Proof::new(mul(&proof_a), proof_b, mul(&proof_c));
+
+    let mut invalid_inputs = public_inputs.to_vec();
+    invalid_inputs[0][31] ^= 1;
+
+    let mut batch = Groth16BatchVerifier::new();
+    assert_eq!(batch.verify(), Err(Groth16Error::InvalidProof));
+
+    batch.push(&proof, public_inputs, &vk).unwrap();
+    batch.push(&proof, public_inputs, &vk).unwrap();
+    batch
+        .push(&scaled_proof, public_inputs, &scaled_vk)
+        .unwrap();
+    assert_eq!(batch.len(), 3);
+    assert!(batch.verify().unwrap());
+
+    batch.push(&proof, &invalid_inputs, &vk).unwrap();
+    assert_eq!(batch.verify(), Err(Groth16Error::VerificationFailed));
+
+    let mut batch = Groth16BatchVerifier::new();
+    batch.push(&proof, public_inputs, &vk).unwrap();
+    batch.push(&scaled_proof, public_inputs, &vk).unwrap();
+    assert_eq!(batch.verify(), Err(Groth16Error::VerificationFailed));
+}
+
 pub fn alt_bn128_g1_neg(input: &[u8]) -> Result<Vec<u8>, AltBn128Error> {
     use std::ops::Neg;
     type G1 = ark_bn254::g1::G1Affine;
@@ -0,0 +1,100 @@
+//! Proving benchmark, compare the serial and the parallel paths with
+//! `cargo bench --bench prove --features std` and
+//! `cargo bench --bench prove --features std,parallel`.
+
+use std::time::{Duration, Instant};
+
+use bellman::{groth16::generate_random_parameters, Circuit, ConstraintSystem, SynthesisError};
+use bls12_381::{Bls12, Scalar};
+use ff::{Field, PrimeField};
+use groth16::{
+    assignments::{create_params, extract_assignments, extract_circuit},
+    prover::create_proof,
+    verifier::verify_proof,
+};
+use rand::thread_rng;
+
+const ITERATIONS: u32 = 5;
+
+/// Proves the knowledge of `x` such that `x^(2^n) == y`
+#[derive(Clone)]
+struct Squares<S: PrimeField> {
+    x: Option<S>,
+    n: usize,
+}
+
+impl<S: PrimeField> Circuit<S> for Squares<S> {
+    fn synthesize<CS: ConstraintSystem<S>>(self, cs: &mut CS) -> Result<(), SynthesisError> {
+        let mut value = self.x;
+        let mut var = cs.alloc(|| "x", || value.ok_or(SynthesisError::AssignmentMissing))?;
+
+        for i in 0..self.n {
+            let next_value = value.map(|v| v.square());
+            let f = || next_value.ok_or(SynthesisError::AssignmentMissing);
+            let next = if i + 1 == self.n {
+                cs.alloc_input(|| "y", f)?
+            } else {
+                cs.alloc(|| "x^(2^i)", f)?
+            };
+            cs.enforce(|| "square", |lc| lc + var, |lc| lc + var, |lc| lc + next);
+            var = next;
+            value = next_value;
+        }
+
+        Ok(())
+    }
+}
+
+fn bench<T>(name: &str, mut f: impl FnMut() -> T) -> T {
+    let mut res = f();
+    let mut total = Duration::ZERO;
+    for _ in 0..ITERATIONS {
+        let start = Instant::now();
+        res = f();
+        total += start.elapsed();
+    }
+    println!("{:<32} {:>12.3?}", name, total / ITERATIONS);
+    res
+}
+
+fn main() {
+    let mut rng = thread_rng();
+    let mode = if cfg!(feature = "parallel") {
+        "parallel"
+    } else {
+        "serial"
+    };
+    println!("groth16 prover benchmark ({mode})");
+
+    for n in [1 << 10, 1 << 14, 1 << 16] {
+        let circuit = Squares {
+            x: Some(Scalar::random(&mut rng)),
+            n,
+        };
+        let empty = Squares::<Scalar> { x: None, n };
+
+        let params = create_params(
+            generate_random_parameters::<Bls12, _, _>(empty.clone(), &mut rng).unwrap(),
+        );
+        let vk = params.vk.clone();
+        let qap = extract_circuit(empty).unwrap();
+        let cs = extract_assignments::<_, Bls12>(circuit).unwrap();
+        let (inputs, aux) = cs.get_assignments();
+        let num_constraints = cs.num_constraints();
+        let (r, s) = (Scalar::random(&mut rng), Scalar::random(&mut rng));
+
+        let proof = bench(&format!("create_proof ({n} constraints)"), || {
+            create_proof(
+                params.clone(),
+                &inputs,
+                &aux,
+                r,
+                s,
+                qap.clone(),
+                num_constraints,
+            )
+        });
+
+        verify_proof(&proof, &inputs[1..], &vk).expect("invalid proof");
+    }
+}
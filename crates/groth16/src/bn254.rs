@@ -0,0 +1,131 @@
+//! BN254 (alt_bn128) engine, used by the circom circuits of Albus, and the conversion
+//! of proofs and verification keys into the big-endian byte layout of `groth16-solana`
+//! (the layout of the `ProofData` and `VerificationKey` of the Albus program).
+
+use ff::PrimeField;
+use pairing::group::prime::PrimeCurveAffine;
+
+pub use halo2curves::bn256::{Bn256 as Bn254, Fq, Fq2, Fr, G1Affine, G2Affine};
+
+use crate::{Proof, VerificationKey};
+
+#[cfg(not(any(test, feature = "std")))]
+use alloc::vec::Vec;
+
+/// Proof in the `groth16-solana` layout, `a` is negated as expected by its verifier
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SolanaProof {
+    pub a: [u8; 64],
+    pub b: [u8; 128],
+    pub c: [u8; 64],
+}
+
+/// Verification key in the `groth16-solana` layout
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SolanaVerificationKey {
+    pub alpha: [u8; 64],
+    pub beta: [u8; 128],
+    pub gamma: [u8; 128],
+    pub delta: [u8; 128],
+    pub ic: Vec<[u8; 64]>,
+}
+
+impl From<&Proof<Bn254>> for SolanaProof {
+    fn from(proof: &Proof<Bn254>) -> Self {
+        Self {
+            a: g1_to_bytes(&-proof.a),
+            b: g2_to_bytes(&proof.b),
+            c: g1_to_bytes(&proof.c),
+        }
+    }
+}
+
+impl From<&VerificationKey<Bn254>> for SolanaVerificationKey {
+    fn from(vk: &VerificationKey<Bn254>) -> Self {
+        Self {
+            alpha: g1_to_bytes(&vk.alpha_g1),
+            beta: g2_to_bytes(&vk.beta_g2),
+            gamma: g2_to_bytes(&vk.gamma_g2),
+            delta: g2_to_bytes(&vk.delta_g2),
+            ic: vk.ic.iter().map(g1_to_bytes).collect(),
+        }
+    }
+}
+
+/// Converts public inputs to the big-endian layout of the proof request
+pub fn public_inputs_to_bytes(inputs: &[Fr]) -> Vec<[u8; 32]> {
+    inputs.iter().map(field_to_bytes).collect()
+}
+
+/// Encodes a G1 point as `x || y`, the point at infinity is all zeros
+pub fn g1_to_bytes(point: &G1Affine) -> [u8; 64] {
+    let mut res = [0u8; 64];
+    if !bool::from(point.is_identity()) {
+        res[..32].copy_from_slice(&field_to_bytes(&point.x));
+        res[32..].copy_from_slice(&field_to_bytes(&point.y));
+    }
+    res
+}
+
+/// Encodes a G2 point as `x_c1 || x_c0 || y_c1 || y_c0`, the point at infinity is all zeros
+pub fn g2_to_bytes(point: &G2Affine) -> [u8; 128] {
+    let mut res = [0u8; 128];
+    if !bool::from(point.is_identity()) {
+        for (i, c) in [point.x.c1, point.x.c0, point.y.c1, point.y.c0]
+            .iter()
+            .enumerate()
+        {
+            res[i * 32..(i + 1) * 32].copy_from_slice(&field_to_bytes(c));
+        }
+    }
+    res
+}
+
+/// Encodes a field element as 32 big-endian bytes
+fn field_to_bytes<S: PrimeField<Repr = [u8; 32]>>(value: &S) -> [u8; 32] {
+    // the canonical representation is little-endian
+    let mut res = value.to_repr();
+    res.reverse();
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn g1_layout() {
+        let g = G1Affine::generator();
+        let bytes = g1_to_bytes(&g);
+        assert_eq!(bytes[31], 1);
+        assert_eq!(bytes[63], 2);
+        assert!(bytes[..31].iter().chain(&bytes[32..63]).all(|b| *b == 0));
+
+        // `p - 2`
+        let neg = g1_to_bytes(&-g);
+        assert_eq!(&neg[..32], &bytes[..32]);
+        assert_eq!(neg[32..34], [0x30, 0x64]);
+        assert_eq!(neg[63], 0x45);
+
+        assert_eq!(g1_to_bytes(&G1Affine::identity()), [0u8; 64]);
+    }
+
+    #[test]
+    fn g2_layout() {
+        let bytes = g2_to_bytes(&G2Affine::generator());
+        // EIP-197 generator, imaginary part first
+        assert_eq!(bytes[..4], [0x19, 0x8e, 0x93, 0x93]);
+        assert_eq!(bytes[32..36], [0x18, 0x00, 0xde, 0xef]);
+        assert_eq!(bytes[64..68], [0x09, 0x06, 0x89, 0xd0]);
+        assert_eq!(bytes[96..100], [0x12, 0xc8, 0x5e, 0xa5]);
+
+        assert_eq!(g2_to_bytes(&G2Affine::identity()), [0u8; 128]);
+    }
+
+    #[test]
+    fn public_inputs_layout() {
+        let bytes = public_inputs_to_bytes(&[Fr::from(18)]);
+        assert_eq!(bytes[0][31], 18);
+        assert!(bytes[0][..31].iter().all(|b| *b == 0));
+    }
+}
@@ -0,0 +1,792 @@
+//! Loaders for the circom `.r1cs` constraint system and `.wtns` witness files and the
+//! snarkjs `.zkey` proving key, so the circom circuits of Albus can be proved natively,
+//! without node or snarkjs.
+//!
+//! The three formats share the iden3 binary container: a 4-byte magic, a `u32` version
+//! and a list of `(u32 type, u64 size)` sections. Integers and field elements are
+//! little-endian, `.r1cs` and `.wtns` field elements are canonical while `.zkey` ones
+//! are in Montgomery form.
+//!
+//! A `.zkey` binds the constraints to the root of unity of snarkjs and stores its H query
+//! in the odd Lagrange basis (see [HQuery::OddLagrange]), [create_proof] takes care of both.
+
+#[cfg(any(test, feature = "std"))]
+use std::collections::BTreeMap;
+
+#[cfg(not(any(test, feature = "std")))]
+use alloc::{collections::BTreeMap, vec::Vec};
+
+use ff::{Field, PrimeField};
+use halo2curves::CurveAffine;
+
+use crate::{
+    bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G2Affine},
+    poly::fft_params_with_root,
+    prover::{create_proof_with_h_query, HQuery},
+    Parameters, Proof, Terms, VerificationKey, QAP,
+};
+
+const R1CS_MAGIC: &[u8; 4] = b"r1cs";
+const WTNS_MAGIC: &[u8; 4] = b"wtns";
+const ZKEY_MAGIC: &[u8; 4] = b"zkey";
+
+const R1CS_VERSION: u32 = 1;
+const WTNS_VERSION: u32 = 2;
+const ZKEY_VERSION: u32 = 1;
+
+/// Protocol id of Groth16 in the `.zkey` header
+const ZKEY_GROTH16: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CircomError {
+    InvalidMagic,
+    UnsupportedVersion(u32),
+    UnsupportedProtocol(u32),
+    MissingSection(u32),
+    UnexpectedEof,
+    TrailingBytes,
+    /// The prime of the file is not the modulus of the field
+    FieldMismatch,
+    InvalidScalar,
+    InvalidPoint,
+    /// The witness or the proving key was not generated for the constraint system
+    CircuitMismatch,
+}
+
+/// Root of unity of the BN254 scalar field used by snarkjs, `5^t` where `r = t*2^28 + 1`
+/// (halo2curves uses `7^t`)
+pub fn snarkjs_root_of_unity() -> Fr {
+    Fr::from_raw([
+        0x9bd6_1b6e_725b_19f0,
+        0x402d_111e_4111_2ed4,
+        0x00e0_a7eb_8ef6_2abc,
+        0x2a3c_09f0_a58a_7e85,
+    ])
+}
+
+/// Sparse linear combination of wires
+pub type LinearCombination<S> = Vec<(usize, S)>;
+
+/// Constraint `a * b = c`
+pub type Constraint<S> = (
+    LinearCombination<S>,
+    LinearCombination<S>,
+    LinearCombination<S>,
+);
+
+/// Constraint system of a `.r1cs` file, the wires are ordered as
+/// `[one, public outputs, public inputs, private inputs, internal]`
+#[derive(Clone, Debug)]
+pub struct R1cs<S: PrimeField> {
+    pub num_wires: usize,
+    pub num_pub_out: usize,
+    pub num_pub_in: usize,
+    pub num_prv_in: usize,
+    pub constraints: Vec<Constraint<S>>,
+}
+
+impl<S: PrimeField> R1cs<S> {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CircomError> {
+        let sections = read_container(bytes, R1CS_MAGIC, R1CS_VERSION)?;
+
+        let mut header = section(&sections, 1)?;
+        let n8 = header.u32()?;
+        header.prime::<S>(n8)?;
+        let num_wires = header.u32()?;
+        let num_pub_out = header.u32()?;
+        let num_pub_in = header.u32()?;
+        let num_prv_in = header.u32()?;
+        let _num_labels = header.u64()?;
+        let num_constraints = header.u32()?;
+        header.finish()?;
+
+        let mut r = section(&sections, 2)?;
+        let mut lc = || -> Result<LinearCombination<S>, CircomError> {
+            (0..r.u32()?)
+                .map(|_| {
+                    let wire = r.u32()?;
+                    if wire >= num_wires {
+                        return Err(CircomError::CircuitMismatch);
+                    }
+                    Ok((wire, r.scalar(n8)?))
+                })
+                .collect()
+        };
+        let constraints = (0..num_constraints)
+            .map(|_| Ok((lc()?, lc()?, lc()?)))
+            .collect::<Result<Vec<_>, CircomError>>()?;
+        r.finish()?;
+
+        Ok(Self {
+            num_wires,
+            num_pub_out,
+            num_pub_in,
+            num_prv_in,
+            constraints,
+        })
+    }
+
+    /// Number of public wires, including the constant `one`
+    pub fn num_inputs(&self) -> usize {
+        1 + self.num_pub_out + self.num_pub_in
+    }
+
+    /// Number of constraints of the [QAP], including one constraint per public wire
+    pub fn num_constraints(&self) -> usize {
+        self.constraints.len() + self.num_inputs()
+    }
+
+    /// The [QAP] of the constraint system, the `i`-th public wire is enforced by
+    /// the extra constraint `num_constraints + i`, as snarkjs does
+    pub fn qap(&self) -> QAP<S> {
+        fn collect<S: PrimeField>(
+            terms: impl Iterator<Item = (usize, S, usize)>,
+        ) -> (Terms<S>, Vec<usize>) {
+            let mut map: BTreeMap<usize, Vec<(S, usize)>> = BTreeMap::new();
+            for (wire, coeff, constraint) in terms {
+                map.entry(wire).or_default().push((coeff, constraint));
+            }
+            let constraints = map.keys().copied().collect();
+            (map.into_iter().collect(), constraints)
+        }
+
+        let terms = |select: fn(&Constraint<S>) -> &LinearCombination<S>| {
+            self.constraints.iter().enumerate().flat_map(move |(i, c)| {
+                select(c)
+                    .iter()
+                    .map(move |(wire, coeff)| (*wire, *coeff, i))
+            })
+        };
+
+        let n = self.constraints.len();
+        let inputs = (0..self.num_inputs()).map(|i| (i, S::ONE, n + i));
+        let (a, a_constraints) = collect(terms(|c| &c.0).chain(inputs));
+        let (b, b_constraints) = collect(terms(|c| &c.1));
+        let (c, _) = collect(terms(|c| &c.2));
+
+        QAP {
+            a,
+            b,
+            c,
+
+            a_constraints,
+            b_constraints,
+        }
+    }
+
+    /// Splits a witness into the input and aux assignments of the prover
+    pub fn split_witness(&self, mut witness: Vec<S>) -> Result<(Vec<S>, Vec<S>), CircomError> {
+        if witness.len() != self.num_wires || witness.first() != Some(&S::ONE) {
+            return Err(CircomError::CircuitMismatch);
+        }
+        let aux = witness.split_off(self.num_inputs());
+        Ok((witness, aux))
+    }
+}
+
+/// Reads the witness of a `.wtns` file, one value per wire
+pub fn read_witness<S: PrimeField>(bytes: &[u8]) -> Result<Vec<S>, CircomError> {
+    let sections = read_container(bytes, WTNS_MAGIC, WTNS_VERSION)?;
+
+    let mut header = section(&sections, 1)?;
+    let n8 = header.u32()?;
+    header.prime::<S>(n8)?;
+    let num_witness = header.u32()?;
+    header.finish()?;
+
+    let mut r = section(&sections, 2)?;
+    let witness = (0..num_witness)
+        .map(|_| r.scalar(n8))
+        .collect::<Result<Vec<S>, CircomError>>()?;
+    r.finish()?;
+
+    Ok(witness)
+}
+
+/// Groth16 proving key of a snarkjs `.zkey` file
+#[derive(Clone)]
+pub struct ZKey {
+    pub num_vars: usize,
+    pub num_public: usize,
+    pub domain_size: usize,
+
+    pub vk: VerificationKey<Bn254>,
+
+    // A and B queries for all the variables
+    pub a_g1: Vec<G1Affine>,
+    pub b_g1: Vec<G1Affine>,
+    pub b_g2: Vec<G2Affine>,
+
+    // L query of the private variables
+    pub l: Vec<G1Affine>,
+
+    // H query in the odd Lagrange basis
+    // h_i = (L_{2i+1}(tau)/delta)*G1
+    pub h: Vec<G1Affine>,
+}
+
+impl ZKey {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CircomError> {
+        let sections = read_container(bytes, ZKEY_MAGIC, ZKEY_VERSION)?;
+
+        let mut r = section(&sections, 1)?;
+        let protocol = r.u32()? as u32;
+        if protocol != ZKEY_GROTH16 {
+            return Err(CircomError::UnsupportedProtocol(protocol));
+        }
+        r.finish()?;
+
+        let r_inv = montgomery_r_inv();
+
+        let mut header = section(&sections, 2)?;
+        let n8q = header.u32()?;
+        header.prime::<Fq>(n8q)?;
+        let n8r = header.u32()?;
+        header.prime::<Fr>(n8r)?;
+        let num_vars = header.u32()?;
+        let num_public = header.u32()?;
+        let domain_size = header.u32()?;
+        let alpha_g1 = header.g1(&r_inv)?;
+        let beta_g1 = header.g1(&r_inv)?;
+        let beta_g2 = header.g2(&r_inv)?;
+        let gamma_g2 = header.g2(&r_inv)?;
+        let delta_g1 = header.g1(&r_inv)?;
+        let delta_g2 = header.g2(&r_inv)?;
+        header.finish()?;
+
+        if num_vars <= num_public || !domain_size.is_power_of_two() {
+            return Err(CircomError::CircuitMismatch);
+        }
+
+        let g1_section = |ty: u32, n: usize| -> Result<Vec<G1Affine>, CircomError> {
+            let mut r = section(&sections, ty)?;
+            let points = (0..n)
+                .map(|_| r.g1(&r_inv))
+                .collect::<Result<Vec<_>, CircomError>>()?;
+            r.finish()?;
+            Ok(points)
+        };
+
+        let ic = g1_section(3, num_public + 1)?;
+        let a_g1 = g1_section(5, num_vars)?;
+        let b_g1 = g1_section(6, num_vars)?;
+        let b_g2 = {
+            let mut r = section(&sections, 7)?;
+            let points = (0..num_vars)
+                .map(|_| r.g2(&r_inv))
+                .collect::<Result<Vec<_>, CircomError>>()?;
+            r.finish()?;
+            points
+        };
+        let l = g1_section(8, num_vars - num_public - 1)?;
+        let h = g1_section(9, domain_size)?;
+
+        Ok(Self {
+            num_vars,
+            num_public,
+            domain_size,
+            vk: VerificationKey {
+                alpha_g1,
+                beta_g1,
+                beta_g2,
+                gamma_g2,
+                delta_g1,
+                delta_g2,
+                ic,
+            },
+            a_g1,
+            b_g1,
+            b_g2,
+            l,
+            h,
+        })
+    }
+
+    /// The [Parameters] of the proving key for the [QAP] of its constraint system,
+    /// to be used with [HQuery::OddLagrange] and [snarkjs_root_of_unity]
+    pub fn parameters(&self, qap: &QAP<Fr>) -> Result<Parameters<Bn254>, CircomError> {
+        fn select<T: Copy>(points: &[T], vars: &[usize]) -> Result<Vec<T>, CircomError> {
+            vars.iter()
+                .map(|v| points.get(*v).copied().ok_or(CircomError::CircuitMismatch))
+                .collect()
+        }
+
+        Ok(Parameters {
+            vk: self.vk.clone(),
+            h: self.h.clone(),
+            l: self.l.clone(),
+            a_g1: select(&self.a_g1, &qap.a_constraints)?,
+            b_g1: select(&self.b_g1, &qap.b_constraints)?,
+            b_g2: select(&self.b_g2, &qap.b_constraints)?,
+        })
+    }
+}
+
+/// Creates a proof for the witness of a circom circuit with the proving key of its `.zkey`
+pub fn create_proof(
+    zkey: &ZKey,
+    r1cs: &R1cs<Fr>,
+    witness: Vec<Fr>,
+    r: Fr,
+    s: Fr,
+) -> Result<Proof<Bn254>, CircomError> {
+    let num_constraints = r1cs.num_constraints();
+    let (_, domain_size, _): (Fr, usize, u32) =
+        fft_params_with_root(num_constraints, snarkjs_root_of_unity());
+    if zkey.num_vars != r1cs.num_wires
+        || zkey.num_public + 1 != r1cs.num_inputs()
+        || zkey.domain_size != domain_size
+    {
+        return Err(CircomError::CircuitMismatch);
+    }
+
+    let qap = r1cs.qap();
+    let params = zkey.parameters(&qap)?;
+    let (inputs, aux) = r1cs.split_witness(witness)?;
+
+    Ok(create_proof_with_h_query(
+        HQuery::OddLagrange {
+            root_of_unity: snarkjs_root_of_unity(),
+        },
+        params,
+        &inputs,
+        &aux,
+        r,
+        s,
+        qap,
+        num_constraints,
+    ))
+}
+
+/// `2^-256`, field elements of a `.zkey` are stored as `x * 2^256`
+fn montgomery_r_inv() -> Fq {
+    Fq::from(2).pow_vartime([256]).invert().unwrap()
+}
+
+/// The modulus `p` of the field as a little-endian [PrimeField::Repr]
+fn modulus<S: PrimeField>() -> S::Repr {
+    // `-1` is `p - 1`
+    let mut repr = (-S::ONE).to_repr();
+    for b in repr.as_mut().iter_mut() {
+        let (v, carry) = b.overflowing_add(1);
+        *b = v;
+        if !carry {
+            break;
+        }
+    }
+    repr
+}
+
+fn read_container<'a>(
+    bytes: &'a [u8],
+    magic: &[u8; 4],
+    version: u32,
+) -> Result<Vec<(u32, &'a [u8])>, CircomError> {
+    let mut r = Reader { bytes };
+    if r.take(4)? != magic {
+        return Err(CircomError::InvalidMagic);
+    }
+    let v = r.u32()? as u32;
+    if v != version {
+        return Err(CircomError::UnsupportedVersion(v));
+    }
+
+    let sections = (0..r.u32()?)
+        .map(|_| {
+            let ty = r.u32()? as u32;
+            let size = r.u64()?;
+            Ok((ty, r.take(size)?))
+        })
+        .collect::<Result<Vec<_>, CircomError>>()?;
+    r.finish()?;
+
+    Ok(sections)
+}
+
+fn section<'a>(sections: &[(u32, &'a [u8])], ty: u32) -> Result<Reader<'a>, CircomError> {
+    sections
+        .iter()
+        .find(|(t, _)| *t == ty)
+        .map(|(_, bytes)| Reader { bytes })
+        .ok_or(CircomError::MissingSection(ty))
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], CircomError> {
+        if self.bytes.len() < n {
+            return Err(CircomError::UnexpectedEof);
+        }
+        let (head, tail) = self.bytes.split_at(n);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn u32(&mut self) -> Result<usize, CircomError> {
+        let mut buf = [0u8; 4];
+        buf.copy_from_slice(self.take(4)?);
+        Ok(u32::from_le_bytes(buf) as usize)
+    }
+
+    fn u64(&mut self) -> Result<usize, CircomError> {
+        let mut buf = [0u8; 8];
+        buf.copy_from_slice(self.take(8)?);
+        usize::try_from(u64::from_le_bytes(buf)).map_err(|_| CircomError::UnexpectedEof)
+    }
+
+    fn prime<S: PrimeField>(&mut self, n8: usize) -> Result<(), CircomError> {
+        let modulus = modulus::<S>();
+        if modulus.as_ref().len() != n8 || self.take(n8)? != modulus.as_ref() {
+            return Err(CircomError::FieldMismatch);
+        }
+        Ok(())
+    }
+
+    fn scalar<S: PrimeField>(&mut self, n8: usize) -> Result<S, CircomError> {
+        let mut repr = S::Repr::default();
+        if repr.as_ref().len() != n8 {
+            return Err(CircomError::FieldMismatch);
+        }
+        repr.as_mut().copy_from_slice(self.take(n8)?);
+        Option::from(S::from_repr(repr)).ok_or(CircomError::InvalidScalar)
+    }
+
+    fn fq(&mut self, r_inv: &Fq) -> Result<Fq, CircomError> {
+        let value: Fq = self.scalar(32)?;
+        Ok(value * r_inv)
+    }
+
+    fn g1(&mut self, r_inv: &Fq) -> Result<G1Affine, CircomError> {
+        let point = G1Affine {
+            x: self.fq(r_inv)?,
+            y: self.fq(r_inv)?,
+        };
+        if !bool::from(point.is_on_curve()) {
+            return Err(CircomError::InvalidPoint);
+        }
+        Ok(point)
+    }
+
+    fn g2(&mut self, r_inv: &Fq) -> Result<G2Affine, CircomError> {
+        let x = Fq2 {
+            c0: self.fq(r_inv)?,
+            c1: self.fq(r_inv)?,
+        };
+        let y = Fq2 {
+            c0: self.fq(r_inv)?,
+            c1: self.fq(r_inv)?,
+        };
+        let point = G2Affine { x, y };
+        if !bool::from(point.is_on_curve()) {
+            return Err(CircomError::InvalidPoint);
+        }
+        Ok(point)
+    }
+
+    fn finish(&self) -> Result<(), CircomError> {
+        if !self.bytes.is_empty() {
+            return Err(CircomError::TrailingBytes);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pairing::group::Curve;
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::verifier::verify_proof;
+
+    fn container(magic: &[u8; 4], version: u32, sections: Vec<(u32, Vec<u8>)>) -> Vec<u8> {
+        let mut bytes = magic.to_vec();
+        bytes.extend_from_slice(&version.to_le_bytes());
+        bytes.extend_from_slice(&(sections.len() as u32).to_le_bytes());
+        for (ty, data) in sections {
+            bytes.extend_from_slice(&ty.to_le_bytes());
+            bytes.extend_from_slice(&(data.len() as u64).to_le_bytes());
+            bytes.extend_from_slice(&data);
+        }
+        bytes
+    }
+
+    fn u32s(values: &[u32]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+
+    /// `out <== x * x`, wires `[one, out, x]`
+    fn square_r1cs() -> Vec<u8> {
+        let mut header = u32s(&[32]);
+        header.extend_from_slice(modulus::<Fr>().as_ref());
+        header.extend(u32s(&[3, 1, 0, 1]));
+        header.extend_from_slice(&3u64.to_le_bytes());
+        header.extend(u32s(&[1]));
+
+        let mut constraints = vec![];
+        for wire in [2, 2, 1] {
+            constraints.extend(u32s(&[1, wire]));
+            constraints.extend_from_slice(&Fr::ONE.to_repr());
+        }
+
+        container(R1CS_MAGIC, 1, vec![(1, header), (2, constraints)])
+    }
+
+    fn witness(values: &[Fr]) -> Vec<u8> {
+        let mut header = u32s(&[32]);
+        header.extend_from_slice(modulus::<Fr>().as_ref());
+        header.extend(u32s(&[values.len() as u32]));
+        let data = values.iter().flat_map(|v| v.to_repr()).collect();
+
+        container(WTNS_MAGIC, 2, vec![(1, header), (2, data)])
+    }
+
+    #[test]
+    fn r1cs_and_witness() {
+        let r1cs = R1cs::<Fr>::from_bytes(&square_r1cs()).unwrap();
+        assert_eq!(r1cs.num_inputs(), 2);
+        assert_eq!(r1cs.num_constraints(), 3);
+
+        let qap = r1cs.qap();
+        assert_eq!(qap.a_constraints, vec![0, 1, 2]);
+        assert_eq!(qap.b_constraints, vec![2]);
+        assert_eq!(qap.a[1], (1, vec![(Fr::ONE, 2)]));
+        assert_eq!(qap.c, vec![(1, vec![(Fr::ONE, 0)])]);
+
+        let values = [Fr::ONE, Fr::from(9), Fr::from(3)];
+        let witness = read_witness::<Fr>(&witness(&values)).unwrap();
+        assert_eq!(
+            r1cs.split_witness(witness).unwrap(),
+            (values[..2].to_vec(), values[2..].to_vec())
+        );
+
+        let mut invalid = square_r1cs();
+        invalid[4] = 2;
+        assert_eq!(
+            R1cs::<Fr>::from_bytes(&invalid).unwrap_err(),
+            CircomError::UnsupportedVersion(2)
+        );
+        assert_eq!(
+            read_witness::<Fr>(&square_r1cs()).unwrap_err(),
+            CircomError::InvalidMagic
+        );
+    }
+
+    /// Lagrange basis of the domain generated by `omega` at `tau`
+    fn lagrange(omega: Fr, n: usize, tau: Fr) -> Vec<Fr> {
+        let z = tau.pow_vartime([n as u64]) - Fr::ONE;
+        let n_inv = Fr::from(n as u64).invert().unwrap();
+        let mut w = Fr::ONE;
+        (0..n)
+            .map(|_| {
+                let l = w * z * n_inv * (tau - w).invert().unwrap();
+                w *= omega;
+                l
+            })
+            .collect()
+    }
+
+    /// A `.zkey` for [square_r1cs] generated from known toxic waste, like snarkjs does
+    fn square_zkey() -> Vec<u8> {
+        let mut rng = thread_rng();
+        let [tau, alpha, beta, gamma, delta] = [(); 5].map(|_| Fr::random(&mut rng));
+        let r = Fq::from(2).pow_vartime([256]);
+        let fq = |x: &Fq| (x * r).to_repr();
+        let g1 = |x: Fr| {
+            let p = (G1Affine::generator() * x).to_affine();
+            [fq(&p.x), fq(&p.y)].concat()
+        };
+        let g2 = |x: Fr| {
+            let p = (G2Affine::generator() * x).to_affine();
+            [fq(&p.x.c0), fq(&p.x.c1), fq(&p.y.c0), fq(&p.y.c1)].concat()
+        };
+
+        // constraints: x * x = out, one * 0 = 0, out * 0 = 0
+        let (omega, n, _): (Fr, usize, u32) = fft_params_with_root(3, snarkjs_root_of_unity());
+        let l = lagrange(omega, n, tau);
+        let a = [l[1], l[2], l[0]];
+        let b = [Fr::ZERO, Fr::ZERO, l[0]];
+        let c = [Fr::ZERO, l[0], Fr::ZERO];
+        let k = |i: usize| beta * a[i] + alpha * b[i] + c[i];
+
+        let (omega_2n, _, _): (Fr, usize, u32) =
+            fft_params_with_root(2 * n, snarkjs_root_of_unity());
+        let l_2n = lagrange(omega_2n, 2 * n, tau);
+        let delta_inv = delta.invert().unwrap();
+
+        let mut header = u32s(&[32]);
+        header.extend_from_slice(modulus::<Fq>().as_ref());
+        header.extend(u32s(&[32]));
+        header.extend_from_slice(modulus::<Fr>().as_ref());
+        header.extend(u32s(&[3, 1, n as u32]));
+        header.extend(g1(alpha));
+        header.extend(g1(beta));
+        header.extend(g2(beta));
+        header.extend(g2(gamma));
+        header.extend(g1(delta));
+        header.extend(g2(delta));
+
+        let gamma_inv = gamma.invert().unwrap();
+        let sections = vec![
+            (1, u32s(&[ZKEY_GROTH16])),
+            (2, header),
+            (3, (0..2).flat_map(|i| g1(k(i) * gamma_inv)).collect()),
+            (5, a.iter().flat_map(|x| g1(*x)).collect()),
+            (6, b.iter().flat_map(|x| g1(*x)).collect()),
+            (7, b.iter().flat_map(|x| g2(*x)).collect()),
+            (8, g1(k(2) * delta_inv)),
+            (
+                9,
+                (0..n)
+                    .flat_map(|i| g1(l_2n[2 * i + 1] * delta_inv))
+                    .collect(),
+            ),
+        ];
+        container(ZKEY_MAGIC, 1, sections)
+    }
+
+    #[test]
+    fn zkey_prove() {
+        let mut rng = thread_rng();
+        let r1cs = R1cs::<Fr>::from_bytes(&square_r1cs()).unwrap();
+        let zkey = ZKey::from_bytes(&square_zkey()).unwrap();
+        assert_eq!(zkey.domain_size, 4);
+        assert_eq!(zkey.l.len(), 1);
+
+        let witness = vec![Fr::ONE, Fr::from(9), Fr::from(3)];
+        let proof = create_proof(
+            &zkey,
+            &r1cs,
+            witness,
+            Fr::random(&mut rng),
+            Fr::random(&mut rng),
+        )
+        .unwrap();
+        assert!(verify_proof(&proof, &[Fr::from(9)], &zkey.vk).is_ok());
+        assert!(verify_proof(&proof, &[Fr::from(4)], &zkey.vk).is_err());
+
+        let witness = vec![Fr::ONE, Fr::from(9)];
+        assert!(create_proof(&zkey, &r1cs, witness, Fr::ONE, Fr::ONE).is_err());
+
+        let mut invalid = square_zkey();
+        // `x` of alpha, after the container, protocol and field headers
+        let alpha_x = 12 + (12 + 4) + 12 + (4 + 32 + 4 + 32 + 12);
+        invalid[alpha_x] ^= 1;
+        assert_eq!(
+            ZKey::from_bytes(&invalid).err(),
+            Some(CircomError::InvalidPoint)
+        );
+    }
+
+    #[test]
+    fn zkey_prove_groth16_solana() {
+        use crate::bn254::{public_inputs_to_bytes, SolanaProof, SolanaVerificationKey};
+
+        let mut rng = thread_rng();
+        let r1cs = R1cs::<Fr>::from_bytes(&square_r1cs()).unwrap();
+        let zkey = ZKey::from_bytes(&square_zkey()).unwrap();
+        let witness = vec![Fr::ONE, Fr::from(9), Fr::from(3)];
+        let proof = create_proof(
+            &zkey,
+            &r1cs,
+            witness,
+            Fr::random(&mut rng),
+            Fr::random(&mut rng),
+        )
+        .unwrap();
+
+        let proof = SolanaProof::from(&proof);
+        let vk = SolanaVerificationKey::from(&zkey.vk);
+        let vk = groth16_solana::VK {
+            alpha: vk.alpha,
+            beta: vk.beta,
+            gamma: vk.gamma,
+            delta: vk.delta,
+            ic: &vk.ic,
+        };
+        let proof = groth16_solana::Proof::new(proof.a, proof.b, proof.c);
+
+        let inputs = public_inputs_to_bytes(&[Fr::from(9)]);
+        let verifier = groth16_solana::Groth16Verifier::new_strict(&proof, &inputs, &vk).unwrap();
+        assert_eq!(verifier.verify(), Ok(true));
+
+        let inputs = public_inputs_to_bytes(&[Fr::from(4)]);
+        let verifier = groth16_solana::Groth16Verifier::new(&proof, &inputs, &vk).unwrap();
+        assert_eq!(
+            verifier.verify(),
+            Err(groth16_solana::Groth16Error::VerificationFailed)
+        );
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_prove() {
+        use crate::poly::serial;
+
+        let mut rng = thread_rng();
+        let r1cs = R1cs::<Fr>::from_bytes(&square_r1cs()).unwrap();
+        let zkey = ZKey::from_bytes(&square_zkey()).unwrap();
+        let witness = vec![Fr::ONE, Fr::from(9), Fr::from(3)];
+        let (r, s) = (Fr::random(&mut rng), Fr::random(&mut rng));
+
+        let prove = || create_proof(&zkey, &r1cs, witness.clone(), r, s).unwrap();
+        let expected = serial(prove);
+        let proof = prove();
+        assert_eq!(
+            (proof.a, proof.b, proof.c),
+            (expected.a, expected.b, expected.c)
+        );
+        assert!(verify_proof(&expected, &[Fr::from(9)], &zkey.vk).is_ok());
+    }
+
+    /// `c <== a * b` with `a = 3, b = 11`, set up with `snarkjs zkey new`
+    /// and the witness computed by the circom wasm of the circuit
+    #[test]
+    fn snarkjs_prove() {
+        use groth16_solana::{snarkjs::parse_verification_key, Groth16Verifier};
+
+        use crate::bn254::{public_inputs_to_bytes, SolanaProof, SolanaVerificationKey};
+
+        let r1cs =
+            R1cs::<Fr>::from_bytes(include_bytes!("../test-vectors/mycircuit.r1cs")).unwrap();
+        let zkey = ZKey::from_bytes(include_bytes!("../test-vectors/mycircuit.zkey")).unwrap();
+        let witness = read_witness::<Fr>(include_bytes!("../test-vectors/mycircuit.wtns")).unwrap();
+        assert_eq!(witness[1], Fr::from(33));
+
+        let vk = parse_verification_key(include_str!(
+            "../test-vectors/mycircuit_verification_key.json"
+        ))
+        .unwrap();
+        let zkey_vk = SolanaVerificationKey::from(&zkey.vk);
+        assert_eq!(
+            (zkey_vk.alpha, zkey_vk.beta, zkey_vk.gamma, zkey_vk.delta),
+            (vk.alpha, vk.beta, vk.gamma, vk.delta)
+        );
+        assert_eq!(zkey_vk.ic, vk.ic);
+
+        let mut rng = thread_rng();
+        let proof = create_proof(
+            &zkey,
+            &r1cs,
+            witness,
+            Fr::random(&mut rng),
+            Fr::random(&mut rng),
+        )
+        .unwrap();
+        assert!(verify_proof(&proof, &[Fr::from(33)], &zkey.vk).is_ok());
+
+        let proof = SolanaProof::from(&proof);
+        let proof = groth16_solana::Proof::new(proof.a, proof.b, proof.c);
+        let vk = vk.as_vk();
+        let inputs = public_inputs_to_bytes(&[Fr::from(33)]);
+        let verifier = Groth16Verifier::new_strict(&proof, &inputs, &vk).unwrap();
+        assert_eq!(verifier.verify(), Ok(true));
+
+        let inputs = public_inputs_to_bytes(&[Fr::from(34)]);
+        let verifier = Groth16Verifier::new(&proof, &inputs, &vk).unwrap();
+        assert!(verifier.verify().is_err());
+    }
+}
@@ -8,8 +8,13 @@ use alloc::vec::Vec;
 use ff::PrimeField;
 use pairing::Engine;
 
+#[cfg(feature = "bn254")]
+pub mod bn254;
+#[cfg(feature = "circom")]
+pub mod circom;
 mod poly;
 pub mod prover;
+pub mod serialization;
 pub mod verifier;
 
 #[derive(Debug)]
@@ -60,11 +65,14 @@ pub struct Parameters<E: Engine> {
     pub b_g2: Vec<E::G2Affine>,
 }
 
+/// Sparse constraint polynomials: `(variable, [(coefficient, constraint)])`.
+pub type Terms<S> = Vec<(usize, Vec<(S, usize)>)>;
+
 #[derive(Default, Debug, Clone)]
 pub struct QAP<S: PrimeField> {
-    pub a: Vec<(usize, Vec<(S, usize)>)>,
-    pub b: Vec<(usize, Vec<(S, usize)>)>,
-    pub c: Vec<(usize, Vec<(S, usize)>)>,
+    pub a: Terms<S>,
+    pub b: Terms<S>,
+    pub c: Terms<S>,
 
     // Sorted array of variable indices for which
     // constraint polynomials are non zero
@@ -83,6 +91,7 @@ pub mod assignments {
     use pairing::group::ff::{Field, PrimeField};
 
     use super::*;
+    use crate::serialization::{scalar_from_bytes, SerializationError};
     #[derive(Default, Debug)]
     pub struct AnalyzeCircuit<S: PrimeField> {
         input_assignment: Vec<S>,
@@ -114,7 +123,7 @@ pub mod assignments {
             fn collect<S: PrimeField>(
                 v: Vec<(Index, S, usize)>,
                 p: usize,
-            ) -> (Vec<(usize, Vec<(S, usize)>)>, Vec<usize>) {
+            ) -> (Terms<S>, Vec<usize>) {
                 let mut constraints: Vec<usize> = Vec::new();
                 let mut map: HashMap<usize, Vec<(S, usize)>> = HashMap::new();
 
@@ -170,10 +179,25 @@ pub mod assignments {
             (input_assignments, aux_assignments)
         }
 
-        pub fn from_bytes(_: Vec<Vec<u8>>, _: Vec<Vec<u8>>) -> Self {
-            // [`PrimeField::from_repr`] does not allow conversion from &[u8] slices
-            // Deserialization is Engine dependent
-            unimplemented!()
+        /// Restores the assignments serialized with [Self::to_bytes]
+        pub fn from_bytes(
+            input_assignments: Vec<Vec<u8>>,
+            aux_assignments: Vec<Vec<u8>>,
+        ) -> Result<Self, SerializationError> {
+            let decode = |values: Vec<Vec<u8>>| -> Result<Vec<S>, SerializationError> {
+                values.iter().map(|v| scalar_from_bytes(v)).collect()
+            };
+            let input_assignment = decode(input_assignments)?;
+            let aux_assignment = decode(aux_assignments)?;
+
+            Ok(Self {
+                num_inputs: input_assignment.len(),
+                num_aux: aux_assignment.len(),
+                input_assignment,
+                aux_assignment,
+                extract_assignments: true,
+                ..Default::default()
+            })
         }
     }
 
@@ -220,7 +244,7 @@ pub mod assignments {
                 current_constraint: usize,
             ) {
                 for (var, c) in lc.as_ref() {
-                    output.push((var.get_unchecked(), c.clone(), current_constraint))
+                    output.push((var.get_unchecked(), *c, current_constraint))
                 }
             }
 
@@ -269,7 +293,7 @@ pub mod assignments {
             ..Default::default()
         };
 
-        cs.alloc_input(|| "one", || Ok(E::Fr::one()))?;
+        cs.alloc_input(|| "one", || Ok(E::Fr::ONE))?;
         circuit.synthesize(&mut cs)?;
         for i in 0..cs.num_inputs {
             cs.enforce(
@@ -292,7 +316,7 @@ pub mod assignments {
             ..Default::default()
         };
 
-        cs.alloc_input(|| "one", || Ok(S::one()))?;
+        cs.alloc_input(|| "one", || Ok(S::ONE))?;
         circuit.synthesize(&mut cs)?;
         for i in 0..cs.num_inputs {
             cs.enforce(
@@ -347,7 +371,7 @@ pub mod assignments {
             }
         };
 
-        let grothparams = Parameters {
+        Parameters {
             vk: VerificationKey {
                 alpha_g1: params.vk.alpha_g1,
                 beta_g1: params.vk.beta_g1,
@@ -362,8 +386,6 @@ pub mod assignments {
             a_g1,
             b_g1,
             b_g2,
-        };
-
-        grothparams
+        }
     }
 }
@@ -1,18 +1,89 @@
 #[cfg(not(any(test, feature = "std")))]
 use alloc::vec::Vec;
 
+#[cfg(all(test, feature = "parallel"))]
+use core::cell::Cell;
+
 use ff::PrimeField;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+#[cfg(all(test, feature = "parallel"))]
+std::thread_local! {
+    static SERIAL: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Whether the rayon code paths are taken
+#[cfg(all(not(test), feature = "parallel"))]
+pub(crate) fn parallel() -> bool {
+    true
+}
+
+/// Whether the rayon code paths are taken, see [serial]
+#[cfg(all(test, feature = "parallel"))]
+pub(crate) fn parallel() -> bool {
+    !SERIAL.with(Cell::get)
+}
+
+/// Runs `f` on the serial code paths, to compare them with the parallel ones
+#[cfg(all(test, feature = "parallel"))]
+pub(crate) fn serial<T>(f: impl FnOnce() -> T) -> T {
+    SERIAL.with(|s| s.set(true));
+    let res = f();
+    SERIAL.with(|s| s.set(false));
+    res
+}
+
+/// Runs `a` and `b`, in parallel with the `parallel` feature
+fn join<A, B>(a: A, b: B)
+where
+    A: FnOnce() + Send,
+    B: FnOnce() + Send,
+{
+    #[cfg(feature = "parallel")]
+    if parallel() {
+        rayon::join(a, b);
+        return;
+    }
+
+    a();
+    b();
+}
+
+/// Applies `f` to the consecutive chunks of `a`, in parallel with the `parallel` feature
+fn for_each_chunk<S, F>(a: &mut [S], size: usize, f: F)
+where
+    S: Send,
+    F: Fn(usize, &mut [S]) + Send + Sync,
+{
+    #[cfg(feature = "parallel")]
+    if parallel() {
+        a.par_chunks_mut(size)
+            .enumerate()
+            .for_each(|(i, chunk)| f(i, chunk));
+        return;
+    }
+
+    a.chunks_mut(size)
+        .enumerate()
+        .for_each(|(i, chunk)| f(i, chunk));
+}
 
 pub fn fft_params<S: PrimeField>(l: usize) -> (S, usize, u32) {
+    fft_params_with_root(l, S::ROOT_OF_UNITY)
+}
+
+/// Same as [fft_params] for a given primitive `2^S` root of unity
+pub fn fft_params_with_root<S: PrimeField>(l: usize, root_of_unity: S) -> (S, usize, u32) {
     let mut m = 1;
     let mut exp = 0;
 
     while m < l {
-        m = m << 1;
+        m <<= 1;
         exp += 1;
     }
 
-    let mut omega = S::root_of_unity();
+    let mut omega = root_of_unity;
     for _ in exp..S::S {
         omega = omega.square();
     }
@@ -42,36 +113,55 @@ pub fn fft<S: PrimeField>(a: &mut [S], omega: &S, exp: u32) {
 
     let mut m = 1;
     for _ in 0..exp {
-        let w_m = omega.pow_vartime(&[u64::from(n / (2 * m))]);
-
-        let mut k = 0;
-        while k < n {
-            let mut w = S::one();
-            for j in 0..m {
-                let mut t = a[(k + j + m) as usize];
+        let w_m = omega.pow_vartime([u64::from(n / (2 * m))]);
+
+        // butterflies of different blocks are independent
+        let butterflies = |_, block: &mut [S]| {
+            let (lo, hi) = block.split_at_mut(m as usize);
+            let mut w = S::ONE;
+            for (x, y) in lo.iter_mut().zip(hi.iter_mut()) {
+                let mut t = *y;
                 t.mul_assign(&w);
-                let mut tmp = a[(k + j) as usize];
+                let mut tmp = *x;
                 tmp.sub_assign(&t);
-                a[(k + j + m) as usize] = tmp;
-                a[(k + j) as usize].add_assign(&t);
+                *y = tmp;
+                x.add_assign(&t);
                 w.mul_assign(&w_m);
             }
-            k += 2 * m;
-        }
+        };
+
+        for_each_chunk(a, 2 * m as usize, butterflies);
 
         m *= 2;
     }
 }
 
+/// Multiplies `a[i]` by `g^i`
+fn distribute_powers<S: PrimeField>(a: &mut [S], g: S) {
+    #[cfg(feature = "parallel")]
+    let chunk_size = (a.len() / rayon::current_num_threads()).max(1024);
+    #[cfg(not(feature = "parallel"))]
+    let chunk_size = a.len().max(1);
+
+    for_each_chunk(a, chunk_size, |i, chunk| {
+        let mut u = g.pow_vartime([(i * chunk_size) as u64]);
+        for x in chunk.iter_mut() {
+            x.mul_assign(&u);
+            u.mul_assign(&g);
+        }
+    });
+}
+
 pub fn ifft<S: PrimeField>(a: &mut [S], omega: &S, exp: u32) {
     fft(a, &omega.invert().unwrap(), exp);
     let minv = S::from(a.len() as u64).invert().unwrap();
-    for i in a {
-        i.mul_assign(&minv);
-    }
+
+    for_each_chunk(a, 1024, |_, chunk| {
+        chunk.iter_mut().for_each(|i| i.mul_assign(&minv));
+    });
 }
 
-pub fn sub_eval_domain<S: PrimeField>(a: &mut Vec<S>, b: Vec<S>) {
+pub fn sub_eval_domain<S: PrimeField>(a: &mut [S], b: Vec<S>) {
     assert_eq!(a.len(), b.len());
     for (x, y) in a.iter_mut().zip(b.iter()) {
         x.sub_assign(y);
@@ -79,36 +169,35 @@ pub fn sub_eval_domain<S: PrimeField>(a: &mut Vec<S>, b: Vec<S>) {
 }
 
 pub fn coset_fft<S: PrimeField>(a: &mut [S], omega: &S, exp: u32) {
-    let g = S::multiplicative_generator();
-    let mut u = S::one();
-    for x in a.iter_mut() {
-        x.mul_assign(&u);
-        u.mul_assign(&g);
-    }
+    distribute_powers(a, S::MULTIPLICATIVE_GENERATOR);
     fft(a, omega, exp)
 }
 
 pub fn icoset_fft<S: PrimeField>(a: &mut [S], omega: &S, exp: u32) {
     ifft(a, omega, exp);
-    let g = S::multiplicative_generator().invert().unwrap();
-    let mut u = S::one();
-    for x in a.iter_mut() {
-        x.mul_assign(&u);
-        u.mul_assign(&g);
-    }
+    distribute_powers(a, S::MULTIPLICATIVE_GENERATOR.invert().unwrap());
+}
+
+/// Evaluates the polynomial on the odd roots of unity of the `2n` domain,
+/// i.e. on the coset `omega_2n * <omega>`
+pub fn odd_fft<S: PrimeField>(a: &mut [S], omega_2n: S, omega: &S, exp: u32) {
+    debug_assert_eq!(omega_2n.square(), *omega);
+    distribute_powers(a, omega_2n);
+    fft(a, omega, exp)
 }
 
 pub fn coset_mul_assign<S: PrimeField>(a: &mut Vec<S>, mut b: Vec<S>) {
     // let (omega, m, exp): (S, usize, u32) = fft_params(a.len() + b.len());
-    // a.resize(m, S::zero());
-    // b.resize(m, S::zero());
+    // a.resize(m, S::ZERO);
+    // b.resize(m, S::ZERO);
     assert_eq!(a.len(), b.len());
     let (omega, _, exp): (S, usize, u32) = fft_params(a.len());
-    ifft(a.as_mut_slice(), &omega, exp);
-    ifft(b.as_mut_slice(), &omega, exp);
+    let to_coset = |v: &mut [S]| {
+        ifft(v, &omega, exp);
+        coset_fft(v, &omega, exp);
+    };
 
-    coset_fft(a.as_mut_slice(), &omega, exp);
-    coset_fft(b.as_mut_slice(), &omega, exp);
+    join(|| to_coset(a.as_mut_slice()), || to_coset(b.as_mut_slice()));
 
     for (x, y) in a.iter_mut().zip(b.iter()) {
         x.mul_assign(y);
@@ -131,8 +220,8 @@ mod tests {
 
     pub fn mul_coefficient_domain<S: PrimeField>(a: &mut Vec<S>, b: &mut Vec<S>) {
         let (omega, m, exp): (S, usize, u32) = fft_params(a.len() + b.len());
-        a.resize(m, S::zero());
-        b.resize(m, S::zero());
+        a.resize(m, S::ZERO);
+        b.resize(m, S::ZERO);
         fft(a.as_mut_slice(), &omega, exp);
         fft(b.as_mut_slice(), &omega, exp);
 
@@ -175,6 +264,54 @@ mod tests {
         assert_eq!(avals, a2);
     }
 
+    #[test]
+    fn coset_fft_evaluation() {
+        let mut rng = thread_rng();
+        let a: Vec<BlsScalar> = (0..64).map(|_| BlsScalar::random(&mut rng)).collect();
+
+        let (omega, _, exp): (BlsScalar, usize, u32) = fft_params(a.len());
+        let mut evals = a.clone();
+        coset_fft(&mut evals, &omega, exp);
+
+        // evaluations at `g * omega^i`
+        let mut x = BlsScalar::MULTIPLICATIVE_GENERATOR;
+        for eval in evals.iter() {
+            let mut naive = BlsScalar::zero();
+            for coeff in a.iter().rev() {
+                naive.mul_assign(&x);
+                naive.add_assign(coeff);
+            }
+            assert_eq!(*eval, naive);
+            x.mul_assign(&omega);
+        }
+
+        icoset_fft(&mut evals, &omega, exp);
+        assert_eq!(evals, a);
+    }
+
+    #[test]
+    fn odd_fft_evaluation() {
+        let mut rng = thread_rng();
+        let a: Vec<BlsScalar> = (0..64).map(|_| BlsScalar::random(&mut rng)).collect();
+
+        let (omega, _, exp): (BlsScalar, usize, u32) = fft_params(a.len());
+        let (omega_2n, _, _): (BlsScalar, usize, u32) = fft_params(2 * a.len());
+        let mut evals = a.clone();
+        odd_fft(&mut evals, omega_2n, &omega, exp);
+
+        // evaluations at `omega_2n^(2i+1)`
+        let mut x = omega_2n;
+        for eval in evals.iter() {
+            let mut naive = BlsScalar::zero();
+            for coeff in a.iter().rev() {
+                naive.mul_assign(&x);
+                naive.add_assign(coeff);
+            }
+            assert_eq!(*eval, naive);
+            x.mul_assign(&omega);
+        }
+    }
+
     #[test]
     fn polynomial_arithmetic() {
         let mut rng = thread_rng();
@@ -184,7 +321,7 @@ mod tests {
         let mut naive = vec![BlsScalar::zero(); 64];
         for (i, x) in a.iter().enumerate() {
             for (j, y) in b.iter().enumerate() {
-                let mut prod = x.clone();
+                let mut prod = *x;
                 prod.mul_assign(y);
                 naive[i + j].add_assign(prod)
             }
@@ -196,4 +333,33 @@ mod tests {
 
         assert_eq!(naive, a);
     }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_fft() {
+        let mut rng = thread_rng();
+        // several chunks of `distribute_powers`
+        let a: Vec<BlsScalar> = (0..1 << 12).map(|_| BlsScalar::random(&mut rng)).collect();
+        let b: Vec<BlsScalar> = (0..1 << 12).map(|_| BlsScalar::random(&mut rng)).collect();
+        let (omega, _, exp): (BlsScalar, usize, u32) = fft_params(a.len());
+        let (omega_2n, _, _): (BlsScalar, usize, u32) = fft_params(2 * a.len());
+
+        let run = || {
+            let mut evals = a.clone();
+            fft(&mut evals, &omega, exp);
+            let mut coeffs = evals.clone();
+            ifft(&mut coeffs, &omega, exp);
+            let mut coset = a.clone();
+            coset_fft(&mut coset, &omega, exp);
+            let mut odd = a.clone();
+            odd_fft(&mut odd, omega_2n, &omega, exp);
+            let mut product = a.clone();
+            coset_mul_assign(&mut product, b.clone());
+            (evals, coeffs, coset, odd, product)
+        };
+
+        let expected = serial(run);
+        assert_eq!(expected.1, a);
+        assert_eq!(run(), expected);
+    }
 }
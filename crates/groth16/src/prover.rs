@@ -1,11 +1,27 @@
 #[cfg(not(any(test, feature = "std")))]
-use alloc::vec::Vec;
+use alloc::{vec, vec::Vec};
 use core::ops::{Add, AddAssign, Mul, MulAssign, SubAssign};
 
 use ff::{Field, PrimeField};
-use pairing::{group::Group, Engine};
-
-use crate::{poly::*, Parameters, Proof, QAP};
+use pairing::{
+    group::{prime::PrimeCurveAffine, Group},
+    Engine,
+};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{poly::*, Parameters, Proof, Terms, QAP};
+
+/// Basis of the H query of the [Parameters]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HQuery<S> {
+    /// `h_i = (tau^i*Z_x(tau)/delta)*G1`, as generated by bellman
+    Monomial,
+    /// `h_i = (L_{2i+1}(tau)/delta)*G1`, the Lagrange basis of the odd roots of unity
+    /// of the `2n` domain, as stored in snarkjs `.zkey` files. The constraints are
+    /// bound to the powers of `root_of_unity`, which must be the one used by the setup.
+    OddLagrange { root_of_unity: S },
+}
 
 pub fn create_proof<E: Engine>(
     params: Parameters<E>,
@@ -15,12 +31,35 @@ pub fn create_proof<E: Engine>(
     s: E::Fr,
     qap: QAP<E::Fr>,
     num_constraints: usize,
+) -> Proof<E> {
+    create_proof_with_h_query(
+        HQuery::Monomial,
+        params,
+        inputs,
+        aux,
+        r,
+        s,
+        qap,
+        num_constraints,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_proof_with_h_query<E: Engine>(
+    h_query: HQuery<E::Fr>,
+    params: Parameters<E>,
+    inputs: &[E::Fr],
+    aux: &[E::Fr],
+    r: E::Fr,
+    s: E::Fr,
+    qap: QAP<E::Fr>,
+    num_constraints: usize,
 ) -> Proof<E> {
     fn eval<S: PrimeField>(
         input_assignment: &[S],
         aux_assignment: &[S],
         output: &mut [S],
-        input: Vec<(usize, Vec<(S, usize)>)>,
+        input: Terms<S>,
         p: usize,
     ) {
         for (i, v) in input.into_iter() {
@@ -39,78 +78,74 @@ pub fn create_proof<E: Engine>(
     }
 
     let h = {
-        let (omega, m, exp): (E::Fr, usize, u32) = fft_params(num_constraints);
-        let mut at = vec![E::Fr::zero(); m];
-        let mut bt = vec![E::Fr::zero(); m];
-        let mut ct = vec![E::Fr::zero(); m];
+        let root_of_unity = match h_query {
+            HQuery::Monomial => E::Fr::ROOT_OF_UNITY,
+            HQuery::OddLagrange { root_of_unity } => root_of_unity,
+        };
+        let (omega, m, exp): (E::Fr, usize, u32) =
+            fft_params_with_root(num_constraints, root_of_unity);
+        let mut at = vec![E::Fr::ZERO; m];
+        let mut bt = vec![E::Fr::ZERO; m];
+        let mut ct = vec![E::Fr::ZERO; m];
 
         eval(inputs, aux, &mut at, qap.a, inputs.len());
         eval(inputs, aux, &mut bt, qap.b, inputs.len());
         eval(inputs, aux, &mut ct, qap.c, inputs.len());
 
-        coset_mul_assign(&mut at, bt);
-        ifft(&mut ct, &omega, exp);
-        coset_fft(&mut ct, &omega, exp);
-        sub_eval_domain(&mut at, ct);
-
-        let zinv = {
-            let mut t = <E::Fr as PrimeField>::multiplicative_generator();
-            t = t.pow_vartime(&[at.len() as u64]);
-            t.sub_assign(&E::Fr::one());
-            t.invert().unwrap()
-        };
-
-        for x in at.iter_mut() {
-            x.mul_assign(&zinv);
-        }
-
-        icoset_fft(&mut at, &omega, exp);
+        match h_query {
+            HQuery::Monomial => {
+                coset_mul_assign(&mut at, bt);
+                ifft(&mut ct, &omega, exp);
+                coset_fft(&mut ct, &omega, exp);
+                sub_eval_domain(&mut at, ct);
+
+                let zinv = {
+                    let mut t = <E::Fr as PrimeField>::MULTIPLICATIVE_GENERATOR;
+                    t = t.pow_vartime([at.len() as u64]);
+                    t.sub_assign(&E::Fr::ONE);
+                    t.invert().unwrap()
+                };
+
+                for x in at.iter_mut() {
+                    x.mul_assign(&zinv);
+                }
 
-        let mut acc = E::G1::identity();
+                icoset_fft(&mut at, &omega, exp);
 
-        at.truncate(at.len() - 1);
-        for (i, x) in at.iter().enumerate() {
-            let t = params.h[i].mul(x);
-            acc.add_assign(t);
+                at.truncate(at.len() - 1);
+            }
+            HQuery::OddLagrange { .. } => {
+                // a(x)*b(x) - c(x) has degree below 2n and vanishes on the even roots of
+                // the 2n domain, so its evaluations on the odd roots are all that is needed
+                let (omega_2n, _, _): (E::Fr, usize, u32) =
+                    fft_params_with_root(2 * m, root_of_unity);
+                for t in [&mut at, &mut bt, &mut ct] {
+                    ifft(t, &omega, exp);
+                    odd_fft(t, omega_2n, &omega, exp);
+                }
+                for (x, y) in at.iter_mut().zip(bt.iter()) {
+                    x.mul_assign(y);
+                }
+                sub_eval_domain(&mut at, ct);
+            }
         }
 
-        acc
+        multiexp(&params.h[..at.len()], |i| at[i])
     };
 
     assert_eq!(aux.len(), params.l.len());
-    let l = params
-        .l
-        .iter()
-        .zip(aux.iter())
-        .fold(E::G1::identity(), |acc, (x, y)| acc.add(x.mul(y)));
+    let l = multiexp(&params.l, |i| aux[i]);
 
     let augmented_inputs: Vec<&E::Fr> = inputs.iter().chain(aux.iter()).collect();
 
     assert_eq!(params.a_g1.len(), qap.a_constraints.len());
-    let at_g1 = params
-        .a_g1
-        .iter()
-        .zip(qap.a_constraints.iter())
-        .fold(E::G1::identity(), |acc, (x, y)| {
-            acc.add(x.mul(augmented_inputs[*y]))
-        });
+    let at_g1 = multiexp(&params.a_g1, |i| *augmented_inputs[qap.a_constraints[i]]);
 
     assert_eq!(params.b_g1.len(), qap.b_constraints.len());
-    let bt_g1 = params
-        .b_g1
-        .iter()
-        .zip(qap.b_constraints.iter())
-        .fold(E::G1::identity(), |acc, (x, y)| {
-            acc.add(x.mul(augmented_inputs[*y]))
-        });
+    let bt_g1 = multiexp(&params.b_g1, |i| *augmented_inputs[qap.b_constraints[i]]);
 
-    let bt_g2 = params
-        .b_g2
-        .iter()
-        .zip(qap.b_constraints.iter())
-        .fold(E::G2::identity(), |acc, (x, y)| {
-            acc.add(x.mul(augmented_inputs[*y]))
-        });
+    assert_eq!(params.b_g2.len(), qap.b_constraints.len());
+    let bt_g2 = multiexp(&params.b_g2, |i| *augmented_inputs[qap.b_constraints[i]]);
 
     let mut a = E::G1::identity();
     a.add_assign(params.vk.alpha_g1);
@@ -138,3 +173,57 @@ pub fn create_proof<E: Engine>(
         c: c.into(),
     }
 }
+
+/// Computes `sum bases[i] * scalar(i)`, in parallel with the `parallel` feature.
+/// The result does not depend on the order of additions, so both paths are identical.
+fn multiexp<A, F>(bases: &[A], scalar: F) -> A::Curve
+where
+    A: PrimeCurveAffine,
+    F: Fn(usize) -> A::Scalar + Send + Sync,
+{
+    #[cfg(feature = "parallel")]
+    if parallel() {
+        return bases
+            .par_iter()
+            .enumerate()
+            .map(|(i, x)| x.mul(scalar(i)))
+            .reduce(A::Curve::identity, |acc, t| acc.add(t));
+    }
+
+    bases
+        .iter()
+        .enumerate()
+        .fold(A::Curve::identity(), |acc, (i, x)| {
+            acc.add(x.mul(scalar(i)))
+        })
+}
+
+#[cfg(all(test, feature = "parallel"))]
+mod tests {
+    use bls12_381::{G1Affine, G1Projective, Scalar};
+    use pairing::group::Curve;
+    use rand::thread_rng;
+
+    use super::*;
+
+    #[test]
+    fn parallel_multiexp() {
+        let mut rng = thread_rng();
+        let bases = (0..256)
+            .map(|_| G1Projective::random(&mut rng).to_affine())
+            .collect::<Vec<G1Affine>>();
+        let scalars = (0..256)
+            .map(|_| Scalar::random(&mut rng))
+            .collect::<Vec<_>>();
+
+        let expected = serial(|| multiexp(&bases, |i| scalars[i]));
+        assert!(parallel());
+        assert_eq!(multiexp(&bases, |i| scalars[i]), expected);
+
+        let naive = bases
+            .iter()
+            .zip(scalars.iter())
+            .fold(G1Projective::identity(), |acc, (x, s)| acc + x * s);
+        assert_eq!(expected, naive);
+    }
+}
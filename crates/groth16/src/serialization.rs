@@ -0,0 +1,430 @@
+//! Versioned binary format for [QAP], [Parameters] and assignments, so a circuit can be
+//! extracted once and proved many times (e.g. by a prover service caching the files).
+//!
+//! Every file starts with a 4-byte magic, the format version and the byte lengths of
+//! the engine scalar, G1 and G2 encodings, so a file written for another engine is rejected.
+//! Scalars use the canonical [PrimeField::Repr], points the uncompressed encoding and
+//! lengths and indices are little-endian `u32`/`u64`.
+
+#[cfg(not(any(test, feature = "std")))]
+use alloc::vec::Vec;
+
+use ff::PrimeField;
+use pairing::group::UncompressedEncoding;
+use pairing::Engine;
+
+use crate::{Parameters, Terms, VerificationKey, QAP};
+
+pub const FORMAT_VERSION: u8 = 1;
+
+const QAP_MAGIC: &[u8; 4] = b"G16Q";
+const PARAMS_MAGIC: &[u8; 4] = b"G16P";
+const ASSIGNMENTS_MAGIC: &[u8; 4] = b"G16A";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SerializationError {
+    InvalidMagic,
+    UnsupportedVersion(u8),
+    EngineMismatch,
+    UnexpectedEof,
+    InvalidScalar,
+    InvalidPoint,
+    TrailingBytes,
+}
+
+impl<S: PrimeField> QAP<S> {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(QAP_MAGIC, &[scalar_len::<S>()]);
+        for terms in [&self.a, &self.b, &self.c] {
+            w.u32(terms.len());
+            for (var, coeffs) in terms.iter() {
+                w.u64(*var);
+                w.u32(coeffs.len());
+                for (coeff, constraint) in coeffs.iter() {
+                    w.scalar(coeff);
+                    w.u64(*constraint);
+                }
+            }
+        }
+        for constraints in [&self.a_constraints, &self.b_constraints] {
+            w.u32(constraints.len());
+            constraints.iter().for_each(|c| w.u64(*c));
+        }
+        w.finish()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SerializationError> {
+        let mut r = Reader::new(bytes, QAP_MAGIC, &[scalar_len::<S>()])?;
+        let mut terms = || -> Result<Terms<S>, SerializationError> {
+            (0..r.u32()?)
+                .map(|_| {
+                    let var = r.u64()?;
+                    let coeffs = (0..r.u32()?)
+                        .map(|_| Ok((r.scalar()?, r.u64()?)))
+                        .collect::<Result<Vec<(S, usize)>, SerializationError>>()?;
+                    Ok((var, coeffs))
+                })
+                .collect()
+        };
+        let (a, b, c) = (terms()?, terms()?, terms()?);
+        let a_constraints = r.u64_vec()?;
+        let b_constraints = r.u64_vec()?;
+        r.finish()?;
+
+        Ok(Self {
+            a,
+            b,
+            c,
+            a_constraints,
+            b_constraints,
+        })
+    }
+}
+
+impl<E: Engine> Parameters<E> {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(PARAMS_MAGIC, &engine_lens::<E>());
+        let vk = &self.vk;
+        for p in [&vk.alpha_g1, &vk.beta_g1, &vk.delta_g1] {
+            w.point(p);
+        }
+        for p in [&vk.beta_g2, &vk.gamma_g2, &vk.delta_g2] {
+            w.point(p);
+        }
+        for points in [&vk.ic, &self.h, &self.l, &self.a_g1, &self.b_g1] {
+            w.points(points);
+        }
+        w.points(&self.b_g2);
+        w.finish()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SerializationError> {
+        let mut r = Reader::new(bytes, PARAMS_MAGIC, &engine_lens::<E>())?;
+        let (alpha_g1, beta_g1, delta_g1) = (r.point()?, r.point()?, r.point()?);
+        let (beta_g2, gamma_g2, delta_g2) = (r.point()?, r.point()?, r.point()?);
+        let ic = r.points()?;
+        let (h, l, a_g1, b_g1) = (r.points()?, r.points()?, r.points()?, r.points()?);
+        let b_g2 = r.points()?;
+        r.finish()?;
+
+        Ok(Self {
+            vk: VerificationKey {
+                alpha_g1,
+                beta_g1,
+                beta_g2,
+                gamma_g2,
+                delta_g1,
+                delta_g2,
+                ic,
+            },
+            h,
+            l,
+            a_g1,
+            b_g1,
+            b_g2,
+        })
+    }
+}
+
+/// Serializes the input and aux assignments of a circuit
+pub fn assignments_to_bytes<S: PrimeField>(inputs: &[S], aux: &[S]) -> Vec<u8> {
+    let mut w = Writer::new(ASSIGNMENTS_MAGIC, &[scalar_len::<S>()]);
+    for values in [inputs, aux] {
+        w.u32(values.len());
+        values.iter().for_each(|v| w.scalar(v));
+    }
+    w.finish()
+}
+
+/// Deserializes the input and aux assignments of a circuit
+pub fn assignments_from_bytes<S: PrimeField>(
+    bytes: &[u8],
+) -> Result<(Vec<S>, Vec<S>), SerializationError> {
+    let mut r = Reader::new(bytes, ASSIGNMENTS_MAGIC, &[scalar_len::<S>()])?;
+    let mut values =
+        || -> Result<Vec<S>, SerializationError> { (0..r.u32()?).map(|_| r.scalar()).collect() };
+    let (inputs, aux) = (values()?, values()?);
+    r.finish()?;
+    Ok((inputs, aux))
+}
+
+/// Decodes a scalar from its canonical representation
+pub(crate) fn scalar_from_bytes<S: PrimeField>(bytes: &[u8]) -> Result<S, SerializationError> {
+    let mut repr = S::Repr::default();
+    if repr.as_ref().len() != bytes.len() {
+        return Err(SerializationError::InvalidScalar);
+    }
+    repr.as_mut().copy_from_slice(bytes);
+    Option::from(S::from_repr(repr)).ok_or(SerializationError::InvalidScalar)
+}
+
+fn scalar_len<S: PrimeField>() -> usize {
+    S::Repr::default().as_ref().len()
+}
+
+fn point_len<P: UncompressedEncoding>() -> usize {
+    P::Uncompressed::default().as_ref().len()
+}
+
+fn engine_lens<E: Engine>() -> [usize; 3] {
+    [
+        scalar_len::<E::Fr>(),
+        point_len::<E::G1Affine>(),
+        point_len::<E::G2Affine>(),
+    ]
+}
+
+struct Writer {
+    buf: Vec<u8>,
+}
+
+impl Writer {
+    fn new(magic: &[u8; 4], lens: &[usize]) -> Self {
+        let mut w = Self { buf: Vec::new() };
+        w.buf.extend_from_slice(magic);
+        w.buf.push(FORMAT_VERSION);
+        lens.iter().for_each(|l| w.u32(*l));
+        w
+    }
+
+    fn u32(&mut self, v: usize) {
+        let v = u32::try_from(v).expect("length overflow");
+        self.buf.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn u64(&mut self, v: usize) {
+        self.buf.extend_from_slice(&(v as u64).to_le_bytes());
+    }
+
+    fn scalar<S: PrimeField>(&mut self, v: &S) {
+        self.buf.extend_from_slice(v.to_repr().as_ref());
+    }
+
+    fn point<P: UncompressedEncoding>(&mut self, p: &P) {
+        self.buf.extend_from_slice(p.to_uncompressed().as_ref());
+    }
+
+    fn points<P: UncompressedEncoding>(&mut self, points: &[P]) {
+        self.u32(points.len());
+        points.iter().for_each(|p| self.point(p));
+    }
+
+    fn finish(self) -> Vec<u8> {
+        self.buf
+    }
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(buf: &'a [u8], magic: &[u8; 4], lens: &[usize]) -> Result<Self, SerializationError> {
+        let mut r = Self { buf };
+        if r.bytes(4)? != magic {
+            return Err(SerializationError::InvalidMagic);
+        }
+        match r.bytes(1)?[0] {
+            FORMAT_VERSION => {}
+            v => return Err(SerializationError::UnsupportedVersion(v)),
+        }
+        for len in lens {
+            if r.u32()? != *len {
+                return Err(SerializationError::EngineMismatch);
+            }
+        }
+        Ok(r)
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], SerializationError> {
+        if self.buf.len() < len {
+            return Err(SerializationError::UnexpectedEof);
+        }
+        let (res, rest) = self.buf.split_at(len);
+        self.buf = rest;
+        Ok(res)
+    }
+
+    fn u32(&mut self) -> Result<usize, SerializationError> {
+        let bytes = self.bytes(4)?.try_into().expect("invalid length");
+        Ok(u32::from_le_bytes(bytes) as usize)
+    }
+
+    fn u64(&mut self) -> Result<usize, SerializationError> {
+        let bytes = self.bytes(8)?.try_into().expect("invalid length");
+        usize::try_from(u64::from_le_bytes(bytes)).map_err(|_| SerializationError::UnexpectedEof)
+    }
+
+    fn u64_vec(&mut self) -> Result<Vec<usize>, SerializationError> {
+        (0..self.u32()?).map(|_| self.u64()).collect()
+    }
+
+    fn scalar<S: PrimeField>(&mut self) -> Result<S, SerializationError> {
+        scalar_from_bytes(self.bytes(scalar_len::<S>())?)
+    }
+
+    fn point<P: UncompressedEncoding>(&mut self) -> Result<P, SerializationError> {
+        let mut repr = P::Uncompressed::default();
+        let len = repr.as_ref().len();
+        repr.as_mut().copy_from_slice(self.bytes(len)?);
+        Option::from(P::from_uncompressed(&repr)).ok_or(SerializationError::InvalidPoint)
+    }
+
+    fn points<P: UncompressedEncoding>(&mut self) -> Result<Vec<P>, SerializationError> {
+        (0..self.u32()?).map(|_| self.point()).collect()
+    }
+
+    fn finish(self) -> Result<(), SerializationError> {
+        if !self.buf.is_empty() {
+            return Err(SerializationError::TrailingBytes);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bls12_381::{Bls12, G1Affine, G1Projective, G2Affine, G2Projective, Scalar};
+    use ff::Field;
+    use pairing::group::Group;
+    use rand::thread_rng;
+
+    use super::*;
+
+    #[test]
+    fn qap_round_trip() {
+        let mut rng = thread_rng();
+        let mut terms = || {
+            (0..4)
+                .map(|i| (i, vec![(Scalar::random(&mut rng), i * 2)]))
+                .collect::<Vec<_>>()
+        };
+        let qap = QAP {
+            a: terms(),
+            b: terms(),
+            c: terms(),
+            a_constraints: vec![0, 1, 2, 3],
+            b_constraints: vec![1, 3],
+        };
+
+        let bytes = qap.to_bytes();
+        let decoded = QAP::<Scalar>::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.a, qap.a);
+        assert_eq!(decoded.c, qap.c);
+        assert_eq!(decoded.b_constraints, qap.b_constraints);
+        assert_eq!(decoded.to_bytes(), bytes);
+
+        assert_eq!(
+            QAP::<Scalar>::from_bytes(&bytes[..bytes.len() - 1]).unwrap_err(),
+            SerializationError::UnexpectedEof
+        );
+        let mut invalid = bytes.clone();
+        invalid[4] = FORMAT_VERSION + 1;
+        assert_eq!(
+            QAP::<Scalar>::from_bytes(&invalid).unwrap_err(),
+            SerializationError::UnsupportedVersion(FORMAT_VERSION + 1)
+        );
+    }
+
+    #[test]
+    fn params_round_trip() {
+        let mut rng = thread_rng();
+        let mut g1 = |n: usize| {
+            (0..n)
+                .map(|_| G1Affine::from(G1Projective::random(&mut rng)))
+                .collect::<Vec<_>>()
+        };
+        let (ic, h, l, a_g1, b_g1, vk_g1) = (g1(3), g1(4), g1(2), g1(5), g1(5), g1(3));
+        let b_g2 = (0..5)
+            .map(|_| G2Affine::from(G2Projective::random(&mut rng)))
+            .collect::<Vec<_>>();
+        let params = Parameters::<Bls12> {
+            vk: VerificationKey {
+                alpha_g1: vk_g1[0],
+                beta_g1: vk_g1[1],
+                beta_g2: b_g2[0],
+                gamma_g2: b_g2[1],
+                delta_g1: vk_g1[2],
+                delta_g2: b_g2[2],
+                ic,
+            },
+            h,
+            l,
+            a_g1,
+            b_g1,
+            b_g2,
+        };
+
+        let bytes = params.to_bytes();
+        let decoded = Parameters::<Bls12>::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.vk.ic, params.vk.ic);
+        assert_eq!(decoded.b_g2, params.b_g2);
+        assert_eq!(decoded.to_bytes(), bytes);
+
+        assert_eq!(
+            QAP::<Scalar>::from_bytes(&bytes).unwrap_err(),
+            SerializationError::InvalidMagic
+        );
+        assert_eq!(
+            Parameters::<Bls12>::from_bytes(&bytes[..bytes.len() - 1]).err(),
+            Some(SerializationError::UnexpectedEof)
+        );
+
+        let mut invalid = bytes.clone();
+        invalid[4] = 0;
+        assert_eq!(
+            Parameters::<Bls12>::from_bytes(&invalid).err(),
+            Some(SerializationError::UnsupportedVersion(0))
+        );
+
+        // `x` of alpha, after the header
+        let mut invalid = bytes.clone();
+        invalid[5 + 4 * engine_lens::<Bls12>().len() + 16] ^= 1;
+        assert_eq!(
+            Parameters::<Bls12>::from_bytes(&invalid).err(),
+            Some(SerializationError::InvalidPoint)
+        );
+
+        let mut invalid = bytes.clone();
+        invalid.push(0);
+        assert_eq!(
+            Parameters::<Bls12>::from_bytes(&invalid).err(),
+            Some(SerializationError::TrailingBytes)
+        );
+
+        #[cfg(feature = "bn254")]
+        assert_eq!(
+            Parameters::<crate::bn254::Bn254>::from_bytes(&bytes).err(),
+            Some(SerializationError::EngineMismatch)
+        );
+    }
+
+    #[test]
+    fn assignments_round_trip() {
+        let mut rng = thread_rng();
+        let inputs = vec![Scalar::ONE, Scalar::random(&mut rng)];
+        let aux = (0..8).map(|_| Scalar::random(&mut rng)).collect::<Vec<_>>();
+
+        let bytes = assignments_to_bytes(&inputs, &aux);
+        assert_eq!(assignments_from_bytes(&bytes).unwrap(), (inputs, aux));
+
+        assert_eq!(
+            assignments_from_bytes::<Scalar>(&bytes[..bytes.len() - 1]).unwrap_err(),
+            SerializationError::UnexpectedEof
+        );
+        let mut invalid = bytes.clone();
+        invalid[4] = FORMAT_VERSION + 1;
+        assert_eq!(
+            assignments_from_bytes::<Scalar>(&invalid).unwrap_err(),
+            SerializationError::UnsupportedVersion(FORMAT_VERSION + 1)
+        );
+        // the first input is replaced by the modulus
+        let mut invalid = bytes.clone();
+        invalid[13..45].copy_from_slice((-Scalar::ONE).to_repr().as_ref());
+        invalid[13] += 1;
+        assert_eq!(
+            assignments_from_bytes::<Scalar>(&invalid).unwrap_err(),
+            SerializationError::InvalidScalar
+        );
+    }
+}
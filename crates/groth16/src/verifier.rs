@@ -23,11 +23,11 @@ pub fn verify_proof<E: Engine>(
     let mut rhs = E::pairing(&proof.a, &proof.b);
     rhs.add_assign(E::pairing(
         &acc.into(),
-        &vk.gamma_g2.mul(E::Fr::one().neg()).into(),
+        &vk.gamma_g2.mul(E::Fr::ONE.neg()).into(),
     ));
     rhs.add_assign(E::pairing(
         &proof.c,
-        &vk.delta_g2.mul(E::Fr::one().neg()).into(),
+        &vk.delta_g2.mul(E::Fr::ONE.neg()).into(),
     ));
 
     if E::pairing(&vk.alpha_g1, &vk.beta_g2) == rhs {
@@ -32,6 +32,9 @@ use solana_program::pubkey::Pubkey;
 pub const ALBUS_PROGRAM_ID: Pubkey = pubkey!("ALBUSbdydS2qoQXXeFfr4mqc9LFw5xWmUMdB4tcscHhi");
 pub const ALBUS_DEV_PROGRAM_ID: Pubkey = pubkey!("ALBSoqJrZeZZ423xWme5nozNcozCtMvDWTZZmQLMT3fp");
 
+pub const COMPOSITE_PROOF_REQUEST_DISCRIMINATOR: &[u8] = &[235, 238, 58, 248, 113, 17, 86, 45];
+pub const CIRCUIT_DISCRIMINATOR: &[u8] = &[113, 209, 5, 225, 233, 216, 248, 61];
+pub const ISSUER_DISCRIMINATOR: &[u8] = &[216, 19, 83, 230, 108, 53, 80, 14];
 pub const POLICY_DISCRIMINATOR: &[u8] = &[222, 135, 7, 163, 235, 177, 33, 68];
 pub const PROOF_REQUEST_DISCRIMINATOR: &[u8] = &[78, 10, 176, 254, 231, 33, 111, 224];
 
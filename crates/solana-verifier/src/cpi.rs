@@ -46,6 +46,14 @@ pub fn verify<'info>(
         vec![
             AccountMeta::new(ctx.accounts.proof_request.key(), false),
             AccountMeta::new_readonly(ctx.accounts.circuit.key(), false),
+            AccountMeta::new_readonly(ctx.accounts.circuit_version.key(), false),
+            AccountMeta::new_readonly(ctx.accounts.policy.key(), false),
+            // an omitted optional account is passed as the program id
+            if ctx.accounts.nullifier.key() == program.key() {
+                AccountMeta::new_readonly(program.key(), false)
+            } else {
+                AccountMeta::new(ctx.accounts.nullifier.key(), false)
+            },
             AccountMeta::new(ctx.accounts.authority.key(), true),
             AccountMeta::new_readonly(ctx.accounts.system_program.key(), false),
         ],
@@ -54,6 +62,9 @@ pub fn verify<'info>(
     let account_infos = vec![
         ctx.accounts.proof_request,
         ctx.accounts.circuit,
+        ctx.accounts.circuit_version,
+        ctx.accounts.policy,
+        ctx.accounts.nullifier,
         ctx.accounts.authority,
         ctx.accounts.system_program,
     ];
@@ -61,13 +72,19 @@ pub fn verify<'info>(
     invoke_signed(&ix, &account_infos, ctx.signer_seeds).map_err(Into::into)
 }
 
-#[derive(Accounts)]
+#[derive(Accounts, Clone)]
 pub struct VerifyProofRequest<'info> {
     /// CHECK: account checked in CPI
     pub proof_request: AccountInfo<'info>,
     /// CHECK: account checked in CPI
     pub circuit: AccountInfo<'info>,
     /// CHECK: account checked in CPI
+    pub circuit_version: AccountInfo<'info>,
+    /// CHECK: account checked in CPI
+    pub policy: AccountInfo<'info>,
+    /// CHECK: account checked in CPI, the program account if the policy does not enforce nullifiers
+    pub nullifier: AccountInfo<'info>,
+    /// CHECK: account checked in CPI
     pub authority: AccountInfo<'info>,
     /// CHECK: account checked in CPI
     pub system_program: AccountInfo<'info>,
@@ -0,0 +1,180 @@
+/*
+ * This file is part of Albus code.
+ *
+ * Copyright (c) 2023, mFactory GmbH
+ *
+ * Albus is free software: you can redistribute it
+ * and/or modify it under the terms of the GNU Affero General Public License
+ * as published by the Free Software Foundation, either version 3
+ * of the License, or (at your option) any later version.
+ *
+ * Albus is distributed in the hope that it
+ * will be useful, but WITHOUT ANY WARRANTY; without even the implied
+ * warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.
+ * If not, see <https://www.gnu.org/licenses/agpl-3.0.html>.
+ *
+ * You can be released from the requirements of the Affero GNU General Public License
+ * by purchasing a commercial license. The purchase of such a license is
+ * mandatory as soon as you develop commercial activities using the
+ * Albus code without disclosing the source code of
+ * your own applications.
+ *
+ * The developer of this program can be contacted at <info@albus.finance>.
+ */
+
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+use solana_program::{
+    decode_error::DecodeError,
+    msg,
+    program_error::{PrintProgramError, ProgramError},
+};
+use thiserror::Error;
+
+/// Start of the custom error codes range reserved for the verifier,
+/// so the codes do not collide with the errors of the integrating program
+pub const VERIFIER_ERROR_CODE_OFFSET: u32 = 0xA1B5_0000;
+
+/// Errors that may be returned by the [crate::AlbusVerifier].
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Error, FromPrimitive, PartialEq)]
+pub enum VerificationError {
+    // 0.
+    /// Proof request is proved, but not verified yet
+    #[error("Proof request is not verified")]
+    NotVerified = VERIFIER_ERROR_CODE_OFFSET,
+    /// Proof request is expired
+    #[error("Proof request is expired")]
+    Expired,
+    /// Proof request is not proved yet
+    #[error("Proof request is pending")]
+    Pending,
+    /// Proof request is rejected
+    #[error("Proof request is rejected")]
+    Rejected,
+    /// Public input does not satisfy the required constraint
+    #[error("Signal constraint is not satisfied")]
+    SignalMismatch,
+
+    // 5.
+    /// Account is not owned by the Albus program
+    #[error("Account is not owned by the Albus program")]
+    InvalidProgram,
+    /// Account discriminator does not match the expected account type
+    #[error("Invalid account discriminator")]
+    InvalidDiscriminator,
+    /// Account has no data
+    #[error("Account is empty")]
+    EmptyAccount,
+    /// Account data cannot be decoded
+    #[error("Invalid account data")]
+    InvalidAccountData,
+    /// Account required by the check is not provided
+    #[error("Missing required account")]
+    MissingAccount,
+
+    // 10.
+    /// Proof request policy does not match the expected one
+    #[error("Policy mismatch")]
+    PolicyMismatch,
+    /// Proof request owner does not match the expected one
+    #[error("Owner mismatch")]
+    OwnerMismatch,
+    /// Proof request issuer is not allowed
+    #[error("Issuer mismatch")]
+    IssuerMismatch,
+    /// Proof request issuer is disabled
+    #[error("Issuer is disabled")]
+    IssuerDisabled,
+    /// Service provider does not match the expected one
+    #[error("Service provider mismatch")]
+    ServiceProviderMismatch,
+
+    // 15.
+    /// Circuit does not match the proof request circuit
+    #[error("Circuit mismatch")]
+    CircuitMismatch,
+    /// Policy was recreated after the proof request was created
+    #[error("Policy was recreated")]
+    PolicyRecreated,
+    /// Signal is not found in the circuit
+    #[error("Unknown signal")]
+    UnknownSignal,
+    /// Proof request was verified too long ago
+    #[error("Proof request verification is stale")]
+    Stale,
+    /// Component proof request does not belong to the composite proof request
+    #[error("Component proof request mismatch")]
+    ComponentMismatch,
+
+    // 20.
+    /// Check cannot be applied to a composite proof request
+    #[error("Check is not supported for composite proof requests")]
+    UnsupportedCheck,
+    /// Account data is in a layout the decoder does not support
+    #[error("Unsupported account layout")]
+    UnsupportedLayout,
+}
+
+impl VerificationError {
+    /// Custom program error code
+    pub fn code(self) -> u32 {
+        self as u32
+    }
+
+    /// Decodes the error from the custom program error code
+    pub fn from_code(code: u32) -> Option<Self> {
+        Self::from_u32(code)
+    }
+}
+
+impl From<VerificationError> for ProgramError {
+    fn from(e: VerificationError) -> Self {
+        ProgramError::Custom(e.code())
+    }
+}
+
+impl<T> DecodeError<T> for VerificationError {
+    fn type_of() -> &'static str {
+        "Albus Verifier Error"
+    }
+}
+
+impl PrintProgramError for VerificationError {
+    fn print<E>(&self)
+    where
+        E: 'static + std::error::Error + DecodeError<E> + PrintProgramError + FromPrimitive,
+    {
+        msg!("AlbusVerifierError: {}", self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_codes() {
+        assert_eq!(
+            ProgramError::from(VerificationError::NotVerified),
+            ProgramError::Custom(VERIFIER_ERROR_CODE_OFFSET)
+        );
+        assert_eq!(
+            VerificationError::from_code(VerificationError::UnknownSignal.code()),
+            Some(VerificationError::UnknownSignal)
+        );
+        assert_eq!(VerificationError::from_code(0), None);
+        assert_eq!(
+            VerificationError::Expired.code(),
+            VERIFIER_ERROR_CODE_OFFSET + 1
+        );
+        assert_eq!(
+            VerificationError::Expired.to_string(),
+            "Proof request is expired"
+        );
+    }
+}
@@ -29,35 +29,99 @@
 mod constants;
 #[cfg(feature = "cpi")]
 mod cpi;
+mod error;
+mod signals;
+mod state;
 mod utils;
 
-use crate::constants::{ALBUS_DEV_PROGRAM_ID, ALBUS_PROGRAM_ID, PROOF_REQUEST_DISCRIMINATOR};
+pub use crate::constants::{ALBUS_DEV_PROGRAM_ID, ALBUS_PROGRAM_ID};
+pub use crate::error::{VerificationError, VERIFIER_ERROR_CODE_OFFSET};
+pub use crate::signals::{SignalConstraint, SignalValue, Signals};
+pub use crate::state::{
+    CircuitStatus, CircuitView, CompositeProofRequestView, IssuerView, PolicyRuleOperator,
+    PolicyRuleView, PolicyView, ProofDataView, ProofRequestStatus, ProofRequestView,
+    VerificationKeyView,
+};
+pub use crate::utils::{
+    find_policy_address, find_proof_request_address, find_service_provider_address,
+};
+
 use crate::utils::cmp_pubkeys;
-use arrayref::array_ref;
-use arrayref::array_refs;
 use solana_program::{
     account_info::AccountInfo, clock::Clock, msg, program_error::ProgramError, pubkey::Pubkey,
     sysvar::Sysvar,
 };
 
 pub struct AlbusVerifier<'a, 'info> {
-    /// Proof request address
+    /// Proof request address, either a `ProofRequest` or a `CompositeProofRequest`
     proof_request: &'a AccountInfo<'info>,
+    /// (optional) Accepted Albus program ids, defaults to [ALBUS_PROGRAM_ID] and [ALBUS_DEV_PROGRAM_ID]
+    program_ids: Option<Vec<Pubkey>>,
     /// (optional) Proof request owner's address
     proof_request_owner: Option<Pubkey>,
     /// (optional) Policy address
     policy: Option<Pubkey>,
+    /// (optional) Policy account
+    policy_account: Option<&'a AccountInfo<'info>>,
+    /// (optional) Service provider address
+    service_provider: Option<Pubkey>,
+    /// (optional) Maximum age of the verification, in seconds
+    max_age: Option<i64>,
+    /// (optional) Minimum verification timestamp
+    verified_after: Option<i64>,
+    /// (optional) Minimum verification slot
+    verified_after_slot: Option<u64>,
+    /// (optional) Allowed issuer addresses
+    issuers: Option<Vec<Pubkey>>,
+    /// (optional) Issuer account
+    issuer_account: Option<&'a AccountInfo<'info>>,
+    /// (optional) Circuit account, required for signal constraints
+    circuit: Option<&'a AccountInfo<'info>>,
+    /// (optional) Public input constraints (signal name, array offset, constraint)
+    signal_constraints: Vec<(&'a str, usize, SignalConstraint)>,
+    /// (optional) Component proof requests of a composite proof request
+    components: Option<&'a [AccountInfo<'info>]>,
+    /// (optional) Accounts required to verify the proof request via CPI
+    #[cfg(feature = "cpi")]
+    cpi_accounts: Option<(cpi::VerifyProofRequest<'info>, AccountInfo<'info>)>,
 }
 
 impl<'a, 'info> AlbusVerifier<'a, 'info> {
     pub fn new(proof_request: &'a AccountInfo<'info>) -> AlbusVerifier<'a, 'info> {
         Self {
             proof_request,
+            program_ids: None,
             policy: None,
             proof_request_owner: None,
+            policy_account: None,
+            service_provider: None,
+            max_age: None,
+            verified_after: None,
+            verified_after_slot: None,
+            issuers: None,
+            issuer_account: None,
+            circuit: None,
+            signal_constraints: vec![],
+            components: None,
+            #[cfg(feature = "cpi")]
+            cpi_accounts: None,
         }
     }
 
+    /// Accept accounts owned by the `program_id` Albus program (e.g. localnet or a fork).
+    /// Once set, the default program ids are no longer accepted unless added explicitly.
+    pub fn allow_program_id(self, program_id: Pubkey) -> Self {
+        self.allow_program_ids(&[program_id])
+    }
+
+    /// Accept accounts owned by any of the `program_ids` Albus programs, see [Self::allow_program_id]
+    pub fn allow_program_ids(mut self, program_ids: &[Pubkey]) -> Self {
+        self.program_ids
+            .get_or_insert_with(Vec::new)
+            .extend_from_slice(program_ids);
+        self
+    }
+
     pub fn check_policy(mut self, addr: Pubkey) -> Self {
         self.policy = Some(addr);
         self
@@ -68,130 +132,491 @@ impl<'a, 'info> AlbusVerifier<'a, 'info> {
         self
     }
 
+    /// Load and validate the policy account of the proof request.
+    /// Checks that the policy is bound to the same circuit as the proof request
+    /// and was not recreated after the proof request was created.
+    pub fn check_policy_account(mut self, policy: &'a AccountInfo<'info>) -> Self {
+        self.policy_account = Some(policy);
+        self
+    }
+
+    pub fn check_service_provider(mut self, addr: Pubkey) -> Self {
+        self.service_provider = Some(addr);
+        self
+    }
+
+    /// Require the proof request to be verified within the last `secs` seconds
+    pub fn check_max_age(mut self, secs: i64) -> Self {
+        self.max_age = Some(secs);
+        self
+    }
+
+    /// Require the proof request to be verified at or after the `timestamp`
+    pub fn check_verified_after(mut self, timestamp: i64) -> Self {
+        self.verified_after = Some(timestamp);
+        self
+    }
+
+    /// Require the proof request to be verified at or after the `slot`
+    pub fn check_verified_after_slot(mut self, slot: u64) -> Self {
+        self.verified_after_slot = Some(slot);
+        self
+    }
+
+    /// Require the proof request to be issued by the `addr` issuer
+    pub fn check_issuer(self, addr: Pubkey) -> Self {
+        self.check_issuers(&[addr])
+    }
+
+    /// Require the proof request to be issued by one of the `addrs` issuers
+    pub fn check_issuers(mut self, addrs: &[Pubkey]) -> Self {
+        self.issuers
+            .get_or_insert_with(Vec::new)
+            .extend_from_slice(addrs);
+        self
+    }
+
+    /// Load the issuer account of the proof request and check that it is not disabled
+    pub fn check_issuer_account(mut self, issuer: &'a AccountInfo<'info>) -> Self {
+        self.issuer_account = Some(issuer);
+        self
+    }
+
+    /// Load the circuit account linked to the proof request,
+    /// used to resolve signal names for [Self::require_signal]
+    pub fn check_circuit(mut self, circuit: &'a AccountInfo<'info>) -> Self {
+        self.circuit = Some(circuit);
+        self
+    }
+
+    /// Require the public input of the `name` signal to satisfy the `constraint`.
+    /// For array signals the first element is checked, see [Self::require_signal_at].
+    pub fn require_signal(self, name: &'a str, constraint: SignalConstraint) -> Self {
+        self.require_signal_at(name, 0, constraint)
+    }
+
+    /// Require the `offset` element of the `name` array signal to satisfy the `constraint`
+    pub fn require_signal_at(
+        mut self,
+        name: &'a str,
+        offset: usize,
+        constraint: SignalConstraint,
+    ) -> Self {
+        self.signal_constraints.push((name, offset, constraint));
+        self
+    }
+
+    /// Load the component proof requests of a composite proof request,
+    /// in the order of its sub-policies, and check each of them.
+    /// Required for composite proof requests, their status is derived from the components.
+    pub fn check_components(mut self, components: &'a [AccountInfo<'info>]) -> Self {
+        self.components = Some(components);
+        self
+    }
+
+    /// Verify the proof request on-chain (via CPI) if it is proved but not yet verified.
+    /// The `authority` must be a signer and the proof request must be writable.
+    /// The `circuit_version` is the version account the proof request is pinned to.
+    /// The `nullifier` is the writable nullifier account of the proof request,
+    /// required if the `policy` enforces nullifiers.
+    #[cfg(feature = "cpi")]
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_via_cpi(
+        mut self,
+        circuit: &AccountInfo<'info>,
+        circuit_version: &AccountInfo<'info>,
+        policy: &AccountInfo<'info>,
+        nullifier: Option<&AccountInfo<'info>>,
+        authority: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+        albus_program: &AccountInfo<'info>,
+    ) -> Self {
+        self.cpi_accounts = Some((
+            cpi::VerifyProofRequest {
+                proof_request: self.proof_request.clone(),
+                circuit: circuit.clone(),
+                circuit_version: circuit_version.clone(),
+                policy: policy.clone(),
+                nullifier: nullifier.unwrap_or(albus_program).clone(),
+                authority: authority.clone(),
+                system_program: system_program.clone(),
+            },
+            albus_program.clone(),
+        ));
+        self
+    }
+
     #[cfg(feature = "cpi")]
-    fn cpi_call(&self, _ctx: cpi::VerifyProofRequest) -> Result<(), ProgramError> {
-        unimplemented!()
+    fn cpi_call(
+        &self,
+        accounts: &cpi::VerifyProofRequest<'info>,
+        program: &AccountInfo<'info>,
+    ) -> Result<(), ProgramError> {
+        if !cmp_pubkeys(program.key, self.proof_request.owner) {
+            msg!("AlbusVerifierError: Invalid program {}", program.key);
+            return Err(VerificationError::InvalidProgram.into());
+        }
+
+        let status = {
+            let data = self.proof_request.data.borrow();
+            ProofRequestView::try_from_slice(&data)?.status
+        };
+
+        if status != ProofRequestStatus::Proved {
+            return Ok(());
+        }
+
+        cpi::verify(accounts.clone(), program.clone()).map_err(Into::into)
     }
 
     pub fn run(&self) -> Result<(), ProgramError> {
         self.check_program_account(self.proof_request)?;
-        self.check_proof_request()?;
+        if CompositeProofRequestView::is_composite(&self.proof_request.data.borrow()) {
+            return self.run_composite();
+        }
+        #[cfg(feature = "cpi")]
+        if let Some((accounts, program)) = &self.cpi_accounts {
+            self.cpi_call(accounts, program)?;
+        }
+        let data = self.proof_request.data.borrow();
+        let req = ProofRequestView::try_from_slice(&data)?;
+        self.check_proof_request(&req)?;
+        self.check_policy_data(&req)?;
+        self.check_issuer_data(&req)?;
+        self.check_signals(&req)?;
+        Ok(())
+    }
+
+    /// Checks a composite proof request against its component proof requests.
+    /// The stored aggregate may be out of sync with the components,
+    /// so the status is derived from the components, which are required.
+    fn run_composite(&self) -> Result<(), ProgramError> {
+        #[cfg(feature = "cpi")]
+        let has_cpi = self.cpi_accounts.is_some();
+        #[cfg(not(feature = "cpi"))]
+        let has_cpi = false;
+
+        if has_cpi || self.policy_account.is_some() || !self.signal_constraints.is_empty() {
+            msg!("AlbusVerifierError: Check is not supported for composite proof requests");
+            return Err(VerificationError::UnsupportedCheck.into());
+        }
+
+        let data = self.proof_request.data.borrow();
+        let req = CompositeProofRequestView::try_from_slice(&data)?;
+
+        if let Some(key) = self.policy {
+            if !cmp_pubkeys(key, req.composite_policy) {
+                msg!("AlbusVerifierError: Invalid composite proof request policy");
+                return Err(VerificationError::PolicyMismatch.into());
+            }
+        }
+
+        let Some(components) = self.components else {
+            msg!("AlbusVerifierError: Component proof requests are required");
+            return Err(VerificationError::MissingAccount.into());
+        };
+
+        if components.len() != req.proof_requests.len() {
+            msg!(
+                "AlbusVerifierError: Expected {} component proof requests",
+                req.proof_requests.len()
+            );
+            return Err(VerificationError::ComponentMismatch.into());
+        }
+
+        for (acc, key) in components.iter().zip(&req.proof_requests) {
+            self.check_program_account(acc)?;
+
+            if !cmp_pubkeys(acc.key, key) {
+                msg!(
+                    "AlbusVerifierError: Invalid component proof request {}",
+                    acc.key
+                );
+                return Err(VerificationError::ComponentMismatch.into());
+            }
+        }
+
+        let data = components
+            .iter()
+            .map(|acc| acc.data.borrow())
+            .collect::<Vec<_>>();
+        let components = data
+            .iter()
+            .map(|data| ProofRequestView::try_from_slice(data))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // same aggregation as the `sync_composite_proof_request` instruction
+        let mut status = ProofRequestStatus::Verified;
+        let mut expired_at = 0;
+        let mut verified_at = 0;
+        let mut verified_slot = 0;
+        for component in &components {
+            if !cmp_pubkeys(component.owner, req.owner) {
+                msg!("AlbusVerifierError: Component proof request owner mismatch");
+                return Err(VerificationError::OwnerMismatch.into());
+            }
+
+            status = match (status, component.status) {
+                (ProofRequestStatus::Rejected, _) | (_, ProofRequestStatus::Rejected) => {
+                    ProofRequestStatus::Rejected
+                }
+                (ProofRequestStatus::Pending, _) | (_, ProofRequestStatus::Pending) => {
+                    ProofRequestStatus::Pending
+                }
+                (ProofRequestStatus::Proved, _) | (_, ProofRequestStatus::Proved) => {
+                    ProofRequestStatus::Proved
+                }
+                _ => ProofRequestStatus::Verified,
+            };
+            if component.expired_at > 0 && (expired_at == 0 || component.expired_at < expired_at) {
+                expired_at = component.expired_at;
+            }
+            verified_at = verified_at.max(component.verified_at);
+            verified_slot = verified_slot.max(component.verified_slot);
+        }
+
+        self.check_status(
+            &req.owner,
+            &req.service_provider,
+            expired_at,
+            verified_at,
+            verified_slot,
+            status,
+        )?;
+
+        for component in &components {
+            self.check_status(
+                &component.owner,
+                &component.service_provider,
+                component.expired_at,
+                component.verified_at,
+                component.verified_slot,
+                component.status,
+            )?;
+            self.check_issuer_data(component)?;
+        }
+
         Ok(())
     }
 
     /// Checks if the provided account is a valid program account
     fn check_program_account(&self, acc: &AccountInfo) -> Result<(), ProgramError> {
-        if !cmp_pubkeys(acc.owner, ALBUS_PROGRAM_ID)
-            && !cmp_pubkeys(acc.owner, ALBUS_DEV_PROGRAM_ID)
-        {
+        let is_allowed = match &self.program_ids {
+            Some(ids) => ids.iter().any(|id| cmp_pubkeys(acc.owner, id)),
+            None => {
+                cmp_pubkeys(acc.owner, ALBUS_PROGRAM_ID)
+                    || cmp_pubkeys(acc.owner, ALBUS_DEV_PROGRAM_ID)
+            }
+        };
+        if !is_allowed {
             msg!("AlbusVerifierError: Invalid program account {}", acc.key);
-            return Err(ProgramError::IllegalOwner);
+            return Err(VerificationError::InvalidProgram.into());
         }
         if acc.data_is_empty() {
             msg!("AlbusVerifierError: Program account {} is empty", acc.key);
-            return Err(ProgramError::UninitializedAccount);
+            return Err(VerificationError::EmptyAccount.into());
         }
         Ok(())
     }
 
-    fn check_proof_request(&self) -> Result<(), ProgramError> {
-        let data = self.proof_request.data.borrow();
-        let data = array_ref![data, 0, 218];
-
-        let (
-            discriminator,
-            _service,
-            policy,
-            _circuit,
-            _issuer,
-            owner,
-            _identifier,
-            _created_at,
-            expired_at,
-            _verified_at,
-            _proved_at,
-            _retention_end_date,
-            [status],
-            _bump,
-            // _proof,
-            // _public_inputs,
-        ) = array_refs![data, 8, 32, 32, 32, 32, 32, 8, 8, 8, 8, 8, 8, 1, 1];
-
-        if discriminator != PROOF_REQUEST_DISCRIMINATOR {
-            msg!("AlbusVerifierError: Invalid proof request discriminator");
-            return Err(ProgramError::InvalidAccountData);
-        }
-
+    fn check_proof_request(&self, req: &ProofRequestView) -> Result<(), ProgramError> {
         if let Some(key) = self.policy {
-            if !cmp_pubkeys(key, policy) {
+            if !cmp_pubkeys(key, req.policy) {
                 msg!("AlbusVerifierError: Invalid proof request policy");
-                return Err(ProgramError::InvalidAccountData);
+                return Err(VerificationError::PolicyMismatch.into());
             }
         }
 
+        self.check_status(
+            &req.owner,
+            &req.service_provider,
+            req.expired_at,
+            req.verified_at,
+            req.verified_slot,
+            req.status,
+        )
+    }
+
+    /// Checks the owner, service provider, expiration, status and age of a request
+    fn check_status(
+        &self,
+        owner: &Pubkey,
+        service_provider: &Pubkey,
+        expired_at: i64,
+        verified_at: i64,
+        verified_slot: u64,
+        status: ProofRequestStatus,
+    ) -> Result<(), ProgramError> {
         if let Some(key) = self.proof_request_owner {
             if !cmp_pubkeys(key, owner) {
                 msg!("AlbusVerifierError: Invalid proof request owner");
-                return Err(ProgramError::InvalidAccountData);
+                return Err(VerificationError::OwnerMismatch.into());
+            }
+        }
+
+        if let Some(key) = self.service_provider {
+            if !cmp_pubkeys(key, service_provider) {
+                msg!("AlbusVerifierError: Invalid proof request service provider");
+                return Err(VerificationError::ServiceProviderMismatch.into());
             }
         }
 
-        let expired_at = i64::from_le_bytes(*expired_at);
-        let status = ProofRequestStatus::try_from(*status)?;
         let timestamp = Clock::get()?.unix_timestamp;
 
         if expired_at > 0 && expired_at < timestamp {
             msg!("AlbusVerifierError: Expired!");
-            return Err(ProgramError::Custom(VerificationError::Expired as u32));
+            return Err(VerificationError::Expired.into());
         }
 
         match status {
             ProofRequestStatus::Pending => {
                 msg!("AlbusVerifierError: Proof request is pending");
-                Err(ProgramError::Custom(VerificationError::Pending as u32))
+                return Err(VerificationError::Pending.into());
             }
             ProofRequestStatus::Proved => {
                 msg!("AlbusVerifierError: Proof request is not verified");
-                Err(ProgramError::Custom(VerificationError::NotVerified as u32))
+                return Err(VerificationError::NotVerified.into());
             }
             ProofRequestStatus::Rejected => {
                 msg!("AlbusVerifierError: Proof request is rejected");
-                Err(ProgramError::Custom(VerificationError::Rejected as u32))
+                return Err(VerificationError::Rejected.into());
+            }
+            ProofRequestStatus::Verified => {}
+        }
+
+        if let Some(max_age) = self.max_age {
+            if verified_at.saturating_add(max_age) < timestamp {
+                msg!(
+                    "AlbusVerifierError: Proof request was verified {} seconds ago",
+                    timestamp.saturating_sub(verified_at)
+                );
+                return Err(VerificationError::Stale.into());
+            }
+        }
+
+        if let Some(verified_after) = self.verified_after {
+            if verified_at < verified_after {
+                msg!(
+                    "AlbusVerifierError: Proof request was verified before {}",
+                    verified_after
+                );
+                return Err(VerificationError::Stale.into());
             }
-            ProofRequestStatus::Verified => Ok(()),
         }
+
+        if let Some(slot) = self.verified_after_slot {
+            if verified_slot < slot {
+                msg!(
+                    "AlbusVerifierError: Proof request was verified before slot {}",
+                    slot
+                );
+                return Err(VerificationError::Stale.into());
+            }
+        }
+
+        Ok(())
     }
-}
 
-#[repr(u8)]
-pub enum VerificationError {
-    NotVerified,
-    Expired,
-    Pending,
-    Rejected,
-}
+    fn check_policy_data(&self, req: &ProofRequestView) -> Result<(), ProgramError> {
+        let Some(acc) = self.policy_account else {
+            return Ok(());
+        };
 
-#[repr(u8)]
-#[derive(Default, Debug, Eq, PartialEq, Clone)]
-pub enum ProofRequestStatus {
-    #[default]
-    Pending,
-    Proved,
-    Verified,
-    Rejected,
-}
+        self.check_program_account(acc)?;
+
+        if !cmp_pubkeys(acc.key, req.policy) {
+            msg!("AlbusVerifierError: Invalid policy account {}", acc.key);
+            return Err(VerificationError::PolicyMismatch.into());
+        }
+
+        let data = acc.data.borrow();
+        let policy = PolicyView::try_from_slice(&data)?;
+
+        if !cmp_pubkeys(policy.service_provider, req.service_provider) {
+            msg!("AlbusVerifierError: Policy service provider mismatch");
+            return Err(VerificationError::ServiceProviderMismatch.into());
+        }
+
+        if !cmp_pubkeys(policy.circuit, req.circuit) {
+            msg!("AlbusVerifierError: Policy circuit mismatch");
+            return Err(VerificationError::CircuitMismatch.into());
+        }
+
+        // the policy was deleted and recreated with the same code
+        if policy.created_at > req.created_at {
+            msg!("AlbusVerifierError: Policy was recreated after the proof request");
+            return Err(VerificationError::PolicyRecreated.into());
+        }
+
+        Ok(())
+    }
+
+    fn check_issuer_data(&self, req: &ProofRequestView) -> Result<(), ProgramError> {
+        if let Some(issuers) = &self.issuers {
+            if !issuers.iter().any(|key| cmp_pubkeys(key, req.issuer)) {
+                msg!("AlbusVerifierError: Issuer {} is not allowed", req.issuer);
+                return Err(VerificationError::IssuerMismatch.into());
+            }
+        }
+
+        let Some(acc) = self.issuer_account else {
+            return Ok(());
+        };
+
+        self.check_program_account(acc)?;
+
+        if !cmp_pubkeys(acc.key, req.issuer) {
+            msg!("AlbusVerifierError: Invalid issuer account {}", acc.key);
+            return Err(VerificationError::IssuerMismatch.into());
+        }
+
+        let data = acc.data.borrow();
+        let issuer = IssuerView::try_from_slice(&data)?;
+
+        if issuer.is_disabled {
+            msg!("AlbusVerifierError: Issuer {} is disabled", acc.key);
+            return Err(VerificationError::IssuerDisabled.into());
+        }
+
+        Ok(())
+    }
+
+    fn check_signals(&self, req: &ProofRequestView) -> Result<(), ProgramError> {
+        if self.signal_constraints.is_empty() {
+            return Ok(());
+        }
 
-impl TryFrom<u8> for ProofRequestStatus {
-    type Error = ProgramError;
+        let circuit = self.circuit.ok_or_else(|| {
+            msg!("AlbusVerifierError: Circuit account is required to check signals");
+            VerificationError::MissingAccount
+        })?;
 
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
-        match value {
-            0 => Ok(Self::Pending),
-            1 => Ok(Self::Proved),
-            2 => Ok(Self::Verified),
-            3 => Ok(Self::Rejected),
-            _ => Err(ProgramError::InvalidAccountData),
+        self.check_program_account(circuit)?;
+
+        if !cmp_pubkeys(circuit.key, req.circuit) {
+            msg!("AlbusVerifierError: Invalid proof request circuit");
+            return Err(VerificationError::CircuitMismatch.into());
+        }
+
+        let data = circuit.data.borrow();
+        let signals = CircuitView::try_from_slice(&data)?.signals();
+
+        for (name, offset, constraint) in &self.signal_constraints {
+            let value = signals
+                .index_of(name, *offset)
+                .and_then(|idx| req.public_input(idx))
+                .ok_or_else(|| {
+                    msg!("AlbusVerifierError: Unknown signal {}[{}]", name, offset);
+                    VerificationError::UnknownSignal
+                })?;
+
+            if !constraint.is_satisfied(value) {
+                msg!("AlbusVerifierError: Signal {}[{}] mismatch", name, offset);
+                return Err(VerificationError::SignalMismatch.into());
+            }
         }
+
+        Ok(())
     }
 }
 
@@ -200,6 +625,11 @@ mod test {
     use solana_program::{account_info::AccountInfo, clock::Clock, pubkey::Pubkey};
 
     use super::*;
+    use crate::constants::{
+        CIRCUIT_DISCRIMINATOR, COMPOSITE_PROOF_REQUEST_DISCRIMINATOR, ISSUER_DISCRIMINATOR,
+        POLICY_DISCRIMINATOR, PROOF_REQUEST_DISCRIMINATOR,
+    };
+    use crate::signals::num_to_bytes;
 
     #[test]
     fn test_verified() {
@@ -236,10 +666,48 @@ mod test {
             )
             .check_policy(policy)
             .run(),
-            Err(ProgramError::Custom(VerificationError::Expired as u32))
+            Err(VerificationError::Expired.into())
         );
     }
 
+    #[test]
+    fn test_max_age() {
+        solana_program::program_stubs::set_syscall_stubs(Box::new(SyscallStubs {}));
+
+        let run = |verified_at: i64, max_age: i64, verified_after: i64| {
+            AlbusVerifier::new(
+                &ProofRequestBuilder::new()
+                    .with_status(ProofRequestStatus::Verified)
+                    .with_verified_at(verified_at)
+                    .build(),
+            )
+            .check_max_age(max_age)
+            .check_verified_after(verified_after)
+            .run()
+        };
+
+        // clock timestamp is 5
+        assert_eq!(run(3, 2, 0), Ok(()));
+        assert_eq!(run(3, 1, 0), Err(VerificationError::Stale.into()));
+        assert_eq!(run(3, 10, 3), Ok(()));
+        assert_eq!(run(3, 10, 4), Err(VerificationError::Stale.into()));
+
+        let run = |verified_slot: u64, slot: u64| {
+            AlbusVerifier::new(
+                &ProofRequestBuilder::new()
+                    .with_status(ProofRequestStatus::Verified)
+                    .with_verified_slot(verified_slot)
+                    .build(),
+            )
+            .check_verified_after_slot(slot)
+            .run()
+        };
+
+        assert_eq!(run(100, 100), Ok(()));
+        assert_eq!(run(100, 99), Ok(()));
+        assert_eq!(run(100, 101), Err(VerificationError::Stale.into()));
+    }
+
     #[test]
     fn test_ownership() {
         solana_program::program_stubs::set_syscall_stubs(Box::new(SyscallStubs {}));
@@ -273,16 +741,600 @@ mod test {
             .check_policy(policy)
             .check_owner(Pubkey::new_unique())
             .run(),
-            Err(ProgramError::InvalidAccountData)
+            Err(VerificationError::OwnerMismatch.into())
         );
     }
 
+    #[cfg(feature = "cpi")]
+    #[test]
+    fn test_verify_via_cpi() {
+        use solana_program::instruction::AccountMeta;
+
+        solana_program::program_stubs::set_syscall_stubs(Box::new(SyscallStubs {}));
+
+        let keys = [(); 6].map(|_| Pubkey::new_unique());
+        let mut lamports = [0u64; 6];
+        let mut data = [(); 6].map(|_| vec![]);
+        let [l1, l2, l3, l4, l5, l6] = &mut lamports;
+        let [d1, d2, d3, d4, d5, d6] = &mut data;
+        let [k1, k2, k3, k4, k5, k6] = &keys;
+        let circuit = AccountInfo::new(k1, false, false, l1, d1, k1, false, 0);
+        let circuit_version = AccountInfo::new(k2, false, false, l2, d2, k1, false, 0);
+        let authority = AccountInfo::new(k3, true, true, l3, d3, k3, false, 0);
+        let system_program = AccountInfo::new(k4, false, false, l4, d4, k4, true, 0);
+        let policy = AccountInfo::new(k5, false, false, l5, d5, k1, false, 0);
+        let nullifier = AccountInfo::new(k6, false, true, l6, d6, k1, false, 0);
+        let program_id = ALBUS_PROGRAM_ID;
+        let mut program_lamports = 0;
+        let mut program_data = vec![];
+        let program = AccountInfo::new(
+            &program_id,
+            false,
+            false,
+            &mut program_lamports,
+            &mut program_data,
+            &program_id,
+            true,
+            0,
+        );
+
+        // the program id stands in for an omitted nullifier
+        let mut builders = [ProofRequestBuilder::new(), ProofRequestBuilder::new()];
+        for (builder, (nullifier, nullifier_meta)) in builders.iter_mut().zip([
+            (None, AccountMeta::new_readonly(program_id, false)),
+            (Some(&nullifier), AccountMeta::new(*k6, false)),
+        ]) {
+            let proof_request = builder
+                .with_status(ProofRequestStatus::Proved)
+                .with_policy(*k5)
+                .build();
+
+            EXPECTED_CPI_ACCOUNTS.with(|accounts| {
+                *accounts.borrow_mut() = vec![
+                    AccountMeta::new(*proof_request.key, false),
+                    AccountMeta::new_readonly(*k1, false),
+                    AccountMeta::new_readonly(*k2, false),
+                    AccountMeta::new_readonly(*k5, false),
+                    nullifier_meta,
+                    AccountMeta::new(*k3, true),
+                    AccountMeta::new_readonly(*k4, false),
+                ]
+            });
+
+            assert_eq!(
+                AlbusVerifier::new(&proof_request)
+                    .check_policy(*k5)
+                    .verify_via_cpi(
+                        &circuit,
+                        &circuit_version,
+                        &policy,
+                        nullifier,
+                        &authority,
+                        &system_program,
+                        &program
+                    )
+                    .run(),
+                Ok(())
+            );
+            assert_eq!(
+                ProofRequestView::try_from_slice(&proof_request.data.borrow())
+                    .unwrap()
+                    .status,
+                ProofRequestStatus::Verified
+            );
+        }
+    }
+
+    #[test]
+    fn test_program_ids() {
+        solana_program::program_stubs::set_syscall_stubs(Box::new(SyscallStubs {}));
+
+        let program_id = Pubkey::new_unique();
+        let mut builder = ProofRequestBuilder::new();
+        let proof_request = builder
+            .with_program_id(program_id)
+            .with_status(ProofRequestStatus::Verified)
+            .build();
+
+        assert_eq!(
+            AlbusVerifier::new(&proof_request).run(),
+            Err(VerificationError::InvalidProgram.into())
+        );
+        assert_eq!(
+            AlbusVerifier::new(&proof_request)
+                .allow_program_id(program_id)
+                .run(),
+            Ok(())
+        );
+
+        let mut builder = ProofRequestBuilder::new();
+        let proof_request = builder.with_status(ProofRequestStatus::Verified).build();
+
+        assert_eq!(
+            AlbusVerifier::new(&proof_request)
+                .allow_program_id(program_id)
+                .run(),
+            Err(VerificationError::InvalidProgram.into())
+        );
+        assert_eq!(
+            AlbusVerifier::new(&proof_request)
+                .allow_program_ids(&[program_id, ALBUS_PROGRAM_ID])
+                .run(),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_proof_request_view() {
+        let mut proof = [0u8; ProofDataView::LEN];
+        proof[..64].fill(1);
+        proof[64..192].fill(2);
+        proof[192..].fill(3);
+
+        let mut input = [0u8; 32];
+        input[31] = 18;
+
+        let mut builder = ProofRequestBuilder::new();
+        let acc = builder
+            .with_status(ProofRequestStatus::Proved)
+            .with_proof(proof)
+            .with_public_inputs(vec![[7; 32], input])
+            .with_expired_at(100)
+            .with_circuit_version(3)
+            .with_verified_slot(7)
+            .build();
+
+        let data = acc.data.borrow();
+        let req = ProofRequestView::try_from_slice(&data).unwrap();
+
+        assert_eq!(req.status, ProofRequestStatus::Proved);
+        assert_eq!(req.expired_at, 100);
+        assert_eq!(req.circuit_version, 3);
+        assert_eq!(req.verified_slot, 7);
+        assert_eq!(
+            req.proof,
+            Some(ProofDataView {
+                a: &[1; 64],
+                b: &[2; 128],
+                c: &[3; 64],
+            })
+        );
+        assert_eq!(req.public_inputs_len(), 2);
+        assert_eq!(req.public_input(0), Some(&[7; 32]));
+        assert_eq!(req.public_input_num(1), Some(18));
+        assert_eq!(req.public_input(2), None);
+        assert_eq!(req.public_inputs().count(), 2);
+
+        // truncated account
+        assert_eq!(
+            ProofRequestView::try_from_slice(&data[..data.len() - 16]),
+            Err(VerificationError::UnsupportedLayout.into())
+        );
+
+        // layouts before the circuit version and the verification slot were appended
+        assert_eq!(ProofRequestView::layout_version(data.len() - 8), Some(2));
+        assert_eq!(ProofRequestView::layout_version(data.len() - 12), Some(1));
+        assert_eq!(
+            ProofRequestView::try_from_slice(&data[..data.len() - 8]),
+            Err(VerificationError::UnsupportedLayout.into())
+        );
+
+        // public inputs exceed the account
+        let mut data = data.to_vec();
+        let offset = ProofRequestView::HEADER_LEN + 1 + ProofDataView::LEN;
+        data[offset..offset + 4].copy_from_slice(&5u32.to_le_bytes());
+        assert_eq!(
+            ProofRequestView::try_from_slice(&data),
+            Err(VerificationError::InvalidAccountData.into())
+        );
+    }
+
+    #[test]
+    fn test_signals() {
+        solana_program::program_stubs::set_syscall_stubs(Box::new(SyscallStubs {}));
+
+        let circuit_key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = circuit_data(&["ageBucket"], &["minAge", "countryLookup[2]"]);
+        let view = CircuitView::try_from_slice(&data).unwrap();
+        assert_eq!(view.latest_version, 1);
+        assert_eq!(view.status, CircuitStatus::Finalized);
+        let circuit = AccountInfo::new(
+            &circuit_key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &ALBUS_PROGRAM_ID,
+            false,
+            0,
+        );
+
+        let mut builder = ProofRequestBuilder::new();
+        let proof_request = builder
+            .with_status(ProofRequestStatus::Verified)
+            .with_circuit(circuit_key)
+            .with_public_inputs(vec![
+                num_to_bytes(3),
+                num_to_bytes(18),
+                num_to_bytes(0),
+                num_to_bytes(840),
+            ])
+            .build();
+
+        assert_eq!(
+            AlbusVerifier::new(&proof_request)
+                .check_circuit(&circuit)
+                .require_signal("ageBucket", SignalConstraint::range(2, 4))
+                .require_signal("minAge", SignalConstraint::gte(18))
+                .require_signal_at("countryLookup", 1, SignalConstraint::one_of(&[276, 840]))
+                .run(),
+            Ok(())
+        );
+
+        assert_eq!(
+            AlbusVerifier::new(&proof_request)
+                .check_circuit(&circuit)
+                .require_signal("minAge", SignalConstraint::eq(21))
+                .run(),
+            Err(VerificationError::SignalMismatch.into())
+        );
+
+        // unknown signal
+        assert_eq!(
+            AlbusVerifier::new(&proof_request)
+                .check_circuit(&circuit)
+                .require_signal_at("countryLookup", 2, SignalConstraint::eq(0))
+                .run(),
+            Err(VerificationError::UnknownSignal.into())
+        );
+
+        // missing circuit
+        assert_eq!(
+            AlbusVerifier::new(&proof_request)
+                .require_signal("minAge", SignalConstraint::gte(18))
+                .run(),
+            Err(VerificationError::MissingAccount.into())
+        );
+    }
+
+    #[test]
+    fn test_policy_account() {
+        solana_program::program_stubs::set_syscall_stubs(Box::new(SyscallStubs {}));
+
+        let service_provider = Pubkey::new_unique();
+        let policy_key = Pubkey::new_unique();
+        let circuit = Pubkey::new_unique();
+
+        let check = |data: &mut Vec<u8>, service: Pubkey| {
+            let mut builder = ProofRequestBuilder::new();
+            let proof_request = builder
+                .with_status(ProofRequestStatus::Verified)
+                .with_service_provider(service_provider)
+                .with_policy(policy_key)
+                .with_circuit(circuit)
+                .with_created_at(2)
+                .build();
+            let mut lamports = 0;
+            let policy = AccountInfo::new(
+                &policy_key,
+                false,
+                false,
+                &mut lamports,
+                data,
+                &ALBUS_PROGRAM_ID,
+                false,
+                0,
+            );
+            AlbusVerifier::new(&proof_request)
+                .check_policy(policy_key)
+                .check_policy_account(&policy)
+                .check_service_provider(service)
+                .run()
+        };
+
+        assert_eq!(
+            check(
+                &mut policy_data(service_provider, circuit, 1),
+                service_provider
+            ),
+            Ok(())
+        );
+
+        // invalid service provider
+        assert_eq!(
+            check(
+                &mut policy_data(service_provider, circuit, 1),
+                Pubkey::new_unique()
+            ),
+            Err(VerificationError::ServiceProviderMismatch.into())
+        );
+
+        // circuit mismatch
+        assert_eq!(
+            check(
+                &mut policy_data(service_provider, Pubkey::new_unique(), 1),
+                service_provider
+            ),
+            Err(VerificationError::CircuitMismatch.into())
+        );
+
+        let data = policy_data(service_provider, circuit, 1);
+        let policy = PolicyView::try_from_slice(&data).unwrap();
+        assert_eq!(policy.rules[0].key, "minAge");
+        assert_eq!(policy.rules[0].operator, PolicyRuleOperator::Gte);
+        assert_eq!(policy.circuit_version, 2);
+        assert_eq!(policy.nullifier_signal, "nullifier");
+
+        // operator byte, followed by circuit_version and nullifier_signal
+        let mut data = data;
+        let operator = data.len() - 4 - (4 + "nullifier".len()) - 1;
+        data[operator] = 4;
+        let policy = PolicyView::try_from_slice(&data).unwrap();
+        assert_eq!(policy.rules[0].operator, PolicyRuleOperator::MerkleRoot);
+        data[operator] = 5;
+        assert_eq!(
+            PolicyView::try_from_slice(&data),
+            Err(VerificationError::InvalidAccountData.into())
+        );
+
+        // recreated policy
+        assert_eq!(
+            check(
+                &mut policy_data(service_provider, circuit, 3),
+                service_provider
+            ),
+            Err(VerificationError::PolicyRecreated.into())
+        );
+
+        // invalid discriminator
+        let mut data = policy_data(service_provider, circuit, 1);
+        data[0] = 0;
+        assert_eq!(
+            check(&mut data, service_provider),
+            Err(VerificationError::InvalidDiscriminator.into())
+        );
+    }
+
+    fn policy_data(service_provider: Pubkey, circuit: Pubkey, created_at: i64) -> Vec<u8> {
+        let mut data = POLICY_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(service_provider.as_ref());
+        data.extend_from_slice(circuit.as_ref());
+        for s in ["code", "name", "description"] {
+            data.extend_from_slice(&(s.len() as u32).to_le_bytes());
+            data.extend_from_slice(s.as_bytes());
+        }
+        data.extend_from_slice(&0u32.to_le_bytes()); // expiration_period
+        data.extend_from_slice(&0u32.to_le_bytes()); // retention_period
+        data.extend_from_slice(&0u64.to_le_bytes()); // proof_request_count
+        data.extend_from_slice(&created_at.to_le_bytes());
+        data.push(0); // bump
+        data.extend_from_slice(&1u32.to_le_bytes()); // rules
+        data.extend_from_slice(&6u32.to_le_bytes());
+        data.extend_from_slice(b"minAge");
+        data.extend_from_slice(&[1u8; 32]);
+        data.extend_from_slice(&0u32.to_le_bytes());
+        data.push(1); // operator
+        data.extend_from_slice(&2u32.to_le_bytes()); // circuit_version
+        data.extend_from_slice(&9u32.to_le_bytes()); // nullifier_signal
+        data.extend_from_slice(b"nullifier");
+        data
+    }
+
+    #[test]
+    fn test_issuer() {
+        solana_program::program_stubs::set_syscall_stubs(Box::new(SyscallStubs {}));
+
+        let issuer_key = Pubkey::new_unique();
+
+        let check = |is_disabled: bool, issuers: &[Pubkey]| {
+            let mut builder = ProofRequestBuilder::new();
+            let proof_request = builder
+                .with_status(ProofRequestStatus::Verified)
+                .with_issuer(issuer_key)
+                .build();
+            let mut lamports = 0;
+            let mut data = issuer_data(is_disabled);
+            let issuer = AccountInfo::new(
+                &issuer_key,
+                false,
+                false,
+                &mut lamports,
+                &mut data,
+                &ALBUS_PROGRAM_ID,
+                false,
+                0,
+            );
+            AlbusVerifier::new(&proof_request)
+                .check_issuers(issuers)
+                .check_issuer_account(&issuer)
+                .run()
+        };
+
+        assert_eq!(check(false, &[Pubkey::new_unique(), issuer_key]), Ok(()));
+
+        // not allowed issuer
+        assert_eq!(
+            check(false, &[Pubkey::new_unique()]),
+            Err(VerificationError::IssuerMismatch.into())
+        );
+
+        // disabled issuer
+        assert_eq!(
+            check(true, &[issuer_key]),
+            Err(VerificationError::IssuerDisabled.into())
+        );
+
+        let mut builder = ProofRequestBuilder::new();
+        let proof_request = builder
+            .with_status(ProofRequestStatus::Verified)
+            .with_issuer(issuer_key)
+            .build();
+        assert_eq!(
+            AlbusVerifier::new(&proof_request)
+                .check_issuer(issuer_key)
+                .run(),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_composite() {
+        solana_program::program_stubs::set_syscall_stubs(Box::new(SyscallStubs {}));
+
+        let owner = Pubkey::new_unique();
+        let issuer = Pubkey::new_unique();
+        let composite_policy = Pubkey::new_unique();
+        let keys = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = composite_data(composite_policy, owner, &keys);
+        let req = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &ALBUS_PROGRAM_ID,
+            false,
+            0,
+        );
+
+        let mut builders = [ProofRequestBuilder::new(), ProofRequestBuilder::new()];
+        let components = builders
+            .iter_mut()
+            .zip(keys)
+            .map(|(builder, key)| {
+                builder
+                    .with_address(key)
+                    .with_status(ProofRequestStatus::Verified)
+                    .with_owner(owner)
+                    .with_issuer(issuer)
+                    .build()
+            })
+            .collect::<Vec<_>>();
+
+        let check = |components| {
+            let verifier = AlbusVerifier::new(&req)
+                .check_policy(composite_policy)
+                .check_owner(owner)
+                .check_issuer(issuer);
+            match components {
+                Some(components) => verifier.check_components(components).run(),
+                None => verifier.run(),
+            }
+        };
+
+        assert_eq!(check(Some(&components)), Ok(()));
+
+        // components are required
+        assert_eq!(check(None), Err(VerificationError::MissingAccount.into()));
+        assert_eq!(
+            AlbusVerifier::new(&req).run(),
+            Err(VerificationError::MissingAccount.into())
+        );
+
+        // components in the wrong order
+        let reversed = components.iter().rev().cloned().collect::<Vec<_>>();
+        assert_eq!(
+            check(Some(&reversed)),
+            Err(VerificationError::ComponentMismatch.into())
+        );
+
+        // the stored status is not synced yet, the components are verified
+        req.data.borrow_mut()[COMPOSITE_STATUS_OFFSET] = ProofRequestStatus::Proved as u8;
+        assert_eq!(check(Some(&components)), Ok(()));
+
+        // component was reset after the composite request was synced
+        req.data.borrow_mut()[COMPOSITE_STATUS_OFFSET] = ProofRequestStatus::Verified as u8;
+        components[1].data.borrow_mut()[ProofRequestView::HEADER_LEN - 2] =
+            ProofRequestStatus::Proved as u8;
+        assert_eq!(
+            check(Some(&components)),
+            Err(VerificationError::NotVerified.into())
+        );
+    }
+
+    const COMPOSITE_STATUS_OFFSET: usize = 8 + 32 * 3 + 8 * 3;
+
+    fn composite_data(
+        composite_policy: Pubkey,
+        owner: Pubkey,
+        proof_requests: &[Pubkey],
+    ) -> Vec<u8> {
+        let mut data = COMPOSITE_PROOF_REQUEST_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&[0u8; 32]); // service_provider
+        data.extend_from_slice(composite_policy.as_ref());
+        data.extend_from_slice(owner.as_ref());
+        data.extend_from_slice(&0i64.to_le_bytes()); // created_at
+        data.extend_from_slice(&0i64.to_le_bytes()); // expired_at
+        data.extend_from_slice(&3i64.to_le_bytes()); // verified_at
+        data.push(ProofRequestStatus::Verified as u8);
+        data.push(0); // bump
+        data.extend_from_slice(&(proof_requests.len() as u32).to_le_bytes());
+        proof_requests
+            .iter()
+            .for_each(|key| data.extend_from_slice(key.as_ref()));
+        data
+    }
+
+    fn issuer_data(is_disabled: bool) -> Vec<u8> {
+        let mut data = ISSUER_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&[0u8; 32]); // pubkey
+        data.extend_from_slice(&[0u8; 64]); // zk_pubkey
+        data.extend_from_slice(&[0u8; 32]); // authority
+        data.push(is_disabled as u8);
+        data.extend_from_slice(&0i64.to_le_bytes()); // created_at
+        data.push(0); // bump
+        for s in ["code", "name", "description"] {
+            data.extend_from_slice(&(s.len() as u32).to_le_bytes());
+            data.extend_from_slice(s.as_bytes());
+        }
+        data
+    }
+
+    fn circuit_data(outputs: &[&str], public_signals: &[&str]) -> Vec<u8> {
+        fn write_str(data: &mut Vec<u8>, s: &str) {
+            data.extend_from_slice(&(s.len() as u32).to_le_bytes());
+            data.extend_from_slice(s.as_bytes());
+        }
+        fn write_str_vec(data: &mut Vec<u8>, v: &[&str]) {
+            data.extend_from_slice(&(v.len() as u32).to_le_bytes());
+            v.iter().for_each(|s| write_str(data, s));
+        }
+
+        let mut data = CIRCUIT_DISCRIMINATOR.to_vec();
+        for s in ["code", "name", "description", "wasm_uri", "zkey_uri"] {
+            write_str(&mut data, s);
+        }
+        data.extend_from_slice(&0i64.to_le_bytes()); // created_at
+        data.push(0); // bump
+        data.extend_from_slice(&[0u8; 64 + 128 * 3]); // vk
+        data.extend_from_slice(&0u32.to_le_bytes()); // vk.ic
+        write_str_vec(&mut data, outputs);
+        write_str_vec(&mut data, public_signals);
+        write_str_vec(&mut data, &[]);
+        data.extend_from_slice(&[0u8; 32 * 3]); // wasm_hash, zkey_hash, vk_hash
+        data.extend_from_slice(&1u32.to_le_bytes()); // latest_version
+        data.push(1); // status
+        data
+    }
+
     struct ProofRequestBuilder {
         expired_at: i64,
         owner: Pubkey,
         issuer: Pubkey,
         policy: Pubkey,
+        circuit: Pubkey,
+        service_provider: Pubkey,
+        created_at: i64,
+        verified_at: i64,
+        verified_slot: u64,
         status: u8,
+        proof: Option<[u8; ProofDataView::LEN]>,
+        public_inputs: Vec<[u8; 32]>,
+        circuit_version: u32,
         // --
         _pk: Pubkey,
         _owner: Pubkey,
@@ -297,7 +1349,15 @@ mod test {
                 issuer: Default::default(),
                 owner: Default::default(),
                 policy: Default::default(),
+                circuit: Default::default(),
+                service_provider: Default::default(),
+                created_at: 0,
+                verified_at: 0,
+                verified_slot: 0,
                 status: 0,
+                proof: None,
+                public_inputs: vec![],
+                circuit_version: 0,
                 _pk: Default::default(),
                 _owner: ALBUS_PROGRAM_ID,
                 _lamports: 0,
@@ -305,6 +1365,16 @@ mod test {
             }
         }
 
+        pub fn with_address(&mut self, addr: Pubkey) -> &mut Self {
+            self._pk = addr;
+            self
+        }
+
+        pub fn with_program_id(&mut self, program_id: Pubkey) -> &mut Self {
+            self._owner = program_id;
+            self
+        }
+
         pub fn with_status(&mut self, status: ProofRequestStatus) -> &mut Self {
             self.status = status as u8;
             self
@@ -325,35 +1395,96 @@ mod test {
             self
         }
 
+        pub fn with_service_provider(&mut self, addr: Pubkey) -> &mut Self {
+            self.service_provider = addr;
+            self
+        }
+
+        pub fn with_created_at(&mut self, timestamp: i64) -> &mut Self {
+            self.created_at = timestamp;
+            self
+        }
+
+        pub fn with_verified_at(&mut self, timestamp: i64) -> &mut Self {
+            self.verified_at = timestamp;
+            self
+        }
+
+        pub fn with_verified_slot(&mut self, slot: u64) -> &mut Self {
+            self.verified_slot = slot;
+            self
+        }
+
+        pub fn with_circuit(&mut self, addr: Pubkey) -> &mut Self {
+            self.circuit = addr;
+            self
+        }
+
         pub fn with_expired_at(&mut self, timestamp: i64) -> &mut Self {
             self.expired_at = timestamp;
             self
         }
 
-        fn build(&mut self) -> AccountInfo {
+        pub fn with_proof(&mut self, proof: [u8; ProofDataView::LEN]) -> &mut Self {
+            self.proof = Some(proof);
+            self
+        }
+
+        pub fn with_public_inputs(&mut self, inputs: Vec<[u8; 32]>) -> &mut Self {
+            self.public_inputs = inputs;
+            self
+        }
+
+        pub fn with_circuit_version(&mut self, version: u32) -> &mut Self {
+            self.circuit_version = version;
+            self
+        }
+
+        fn build(&mut self) -> AccountInfo<'_> {
             self._data = [
                 PROOF_REQUEST_DISCRIMINATOR,
-                &Pubkey::new_unique().to_bytes(), //service
+                &self.service_provider.to_bytes(),
                 &self.policy.to_bytes(),
-                &Pubkey::new_unique().to_bytes(), // circuit
+                &self.circuit.to_bytes(),
                 &self.issuer.to_bytes(),
                 &self.owner.to_bytes(),
                 &0u64.to_le_bytes(),
-                &0i64.to_le_bytes(),
+                &self.created_at.to_le_bytes(),
                 &self.expired_at.to_le_bytes(),
-                &0i64.to_le_bytes(),
+                &self.verified_at.to_le_bytes(),
                 &0i64.to_le_bytes(),
                 &0i64.to_le_bytes(),
                 &[self.status],
                 &0u8.to_le_bytes(), // bump
-                &[0u8; 256],        // proof
-                &[0u8; 28],         // public_inputs
             ]
             .into_iter()
             .flatten()
             .copied()
             .collect::<Vec<_>>();
 
+            match &self.proof {
+                Some(proof) => {
+                    self._data.push(1);
+                    self._data.extend_from_slice(proof);
+                }
+                None => self._data.push(0),
+            }
+
+            self._data
+                .extend_from_slice(&(self.public_inputs.len() as u32).to_le_bytes());
+            for input in &self.public_inputs {
+                self._data.extend_from_slice(input);
+            }
+            self._data
+                .extend_from_slice(&self.circuit_version.to_le_bytes());
+            self._data
+                .extend_from_slice(&self.verified_slot.to_le_bytes());
+            // unused space, allocated for two more public inputs
+            self._data.resize(
+                ProofRequestView::BASE_LEN + (self.public_inputs.len() + 2) * 32,
+                0,
+            );
+
             AccountInfo::new(
                 &self._pk,
                 false,
@@ -367,6 +1498,13 @@ mod test {
         }
     }
 
+    #[cfg(feature = "cpi")]
+    std::thread_local! {
+        /// Accounts of the `verify_proof_request` instruction expected by [SyscallStubs]
+        static EXPECTED_CPI_ACCOUNTS: std::cell::RefCell<Vec<solana_program::instruction::AccountMeta>> =
+            const { std::cell::RefCell::new(vec![]) };
+    }
+
     struct SyscallStubs {}
     impl solana_program::program_stubs::SyscallStubs for SyscallStubs {
         fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
@@ -381,5 +1519,32 @@ mod test {
             }
             solana_program::entrypoint::SUCCESS
         }
+
+        #[cfg(feature = "cpi")]
+        fn sol_invoke_signed(
+            &self,
+            instruction: &solana_program::instruction::Instruction,
+            account_infos: &[AccountInfo],
+            _signers_seeds: &[&[&[u8]]],
+        ) -> solana_program::entrypoint::ProgramResult {
+            // emulates a successful `verify_proof_request` instruction
+            if instruction.data == crate::constants::VERIFY_IX_DISCRIMINATOR {
+                EXPECTED_CPI_ACCOUNTS.with(|accounts| {
+                    assert_eq!(instruction.accounts, *accounts.borrow());
+                });
+                assert_eq!(
+                    account_infos.iter().map(|a| *a.key).collect::<Vec<_>>(),
+                    instruction
+                        .accounts
+                        .iter()
+                        .map(|a| a.pubkey)
+                        .collect::<Vec<_>>()
+                );
+                let proof_request = &account_infos[0];
+                proof_request.data.borrow_mut()[ProofRequestView::HEADER_LEN - 2] =
+                    ProofRequestStatus::Verified as u8;
+            }
+            Ok(())
+        }
     }
 }
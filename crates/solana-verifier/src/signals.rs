@@ -0,0 +1,210 @@
+/*
+ * This file is part of Albus code.
+ *
+ * Copyright (c) 2023, mFactory GmbH
+ *
+ * Albus is free software: you can redistribute it
+ * and/or modify it under the terms of the GNU Affero General Public License
+ * as published by the Free Software Foundation, either version 3
+ * of the License, or (at your option) any later version.
+ *
+ * Albus is distributed in the hope that it
+ * will be useful, but WITHOUT ANY WARRANTY; without even the implied
+ * warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.
+ * If not, see <https://www.gnu.org/licenses/agpl-3.0.html>.
+ *
+ * You can be released from the requirements of the Affero GNU General Public License
+ * by purchasing a commercial license. The purchase of such a license is
+ * mandatory as soon as you develop commercial activities using the
+ * Albus code without disclosing the source code of
+ * your own applications.
+ *
+ * The developer of this program can be contacted at <info@albus.finance>.
+ */
+
+use std::collections::HashMap;
+use std::ops::Index;
+
+type SignalKey = String;
+
+/// Port of the Albus program `Signals` helper,
+/// maps circuit signal names to the public inputs positions.
+#[derive(Debug, PartialEq, Eq)]
+pub struct SignalValue {
+    pub index: usize,
+    pub size: usize,
+}
+
+pub struct Signals {
+    data: HashMap<SignalKey, SignalValue>,
+    count: usize,
+}
+
+impl Index<&str> for Signals {
+    type Output = SignalValue;
+
+    fn index(&self, index: &str) -> &Self::Output {
+        self.data.index(index)
+    }
+}
+
+impl Signals {
+    pub fn new<T: AsRef<str>>(signals: &[T]) -> Self {
+        let mut count = 0;
+        let mut data = HashMap::with_capacity(signals.len());
+        for signal in signals {
+            let (name, size) = Self::parse_signal(signal.as_ref());
+            data.insert(name.into(), SignalValue { index: count, size });
+            count += size;
+        }
+        Self { data, count }
+    }
+
+    #[inline]
+    fn parse_signal(s: &str) -> (&str, usize) {
+        match (s.find('['), s.find(']')) {
+            (Some(open), Some(close)) if open < close => {
+                let name = &s[..open];
+                if let Ok(n) = s[open + 1..close].parse::<usize>() {
+                    let (_, m) = Self::parse_signal(&s[close + 1..]);
+                    (name, n * m)
+                } else {
+                    (name, 1)
+                }
+            }
+            _ => (s, 1),
+        }
+    }
+
+    pub fn get(&self, k: &str) -> Option<&SignalValue> {
+        self.data.get(k)
+    }
+
+    /// Resolves the public input index of the signal, `offset` is used for array signals
+    pub fn index_of(&self, k: &str, offset: usize) -> Option<usize> {
+        match self.data.get(k) {
+            Some(s) if offset < s.size => Some(s.index + offset),
+            _ => None,
+        }
+    }
+
+    pub fn has(&self, k: &str) -> bool {
+        self.data.contains_key(k)
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+}
+
+/// Condition applied to a public input value (big-endian scalar field)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignalConstraint {
+    Eq([u8; 32]),
+    Gte([u8; 32]),
+    Lte([u8; 32]),
+    /// Inclusive range
+    Range([u8; 32], [u8; 32]),
+    In(Vec<[u8; 32]>),
+}
+
+impl SignalConstraint {
+    pub fn eq(n: u64) -> Self {
+        Self::Eq(num_to_bytes(n))
+    }
+
+    pub fn gte(n: u64) -> Self {
+        Self::Gte(num_to_bytes(n))
+    }
+
+    pub fn lte(n: u64) -> Self {
+        Self::Lte(num_to_bytes(n))
+    }
+
+    pub fn range(min: u64, max: u64) -> Self {
+        Self::Range(num_to_bytes(min), num_to_bytes(max))
+    }
+
+    pub fn one_of(values: &[u64]) -> Self {
+        Self::In(values.iter().map(|n| num_to_bytes(*n)).collect())
+    }
+
+    /// Checks the `value` against the constraint.
+    /// Big-endian byte arrays are compared lexicographically, which matches numeric order.
+    pub fn is_satisfied(&self, value: &[u8; 32]) -> bool {
+        match self {
+            Self::Eq(v) => value == v,
+            Self::Gte(v) => value >= v,
+            Self::Lte(v) => value <= v,
+            Self::Range(min, max) => value >= min && value <= max,
+            Self::In(values) => values.contains(value),
+        }
+    }
+}
+
+pub fn num_to_bytes(n: u64) -> [u8; 32] {
+    let mut result = [0u8; 32];
+    result[24..].copy_from_slice(&n.to_be_bytes());
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signals() {
+        let signals = Signals::new(&[
+            "currentDate",
+            "minAge",
+            "maxAge",
+            "credentialRoot",
+            "credentialProof[10]",
+            "credentialKey",
+            "issuerPk[2]",
+            "issuerSignature[3]",
+            "encryptedShare[3][4]",
+            "threeLevel[3][4][2]",
+        ]);
+        assert_eq!(signals["currentDate"], SignalValue { index: 0, size: 1 });
+        assert_eq!(signals["credentialRoot"], SignalValue { index: 3, size: 1 });
+        assert_eq!(signals["credentialKey"], SignalValue { index: 14, size: 1 });
+        assert_eq!(
+            signals["encryptedShare"],
+            SignalValue {
+                index: 20,
+                size: 12
+            }
+        );
+        assert_eq!(56, signals.len());
+        assert_eq!(signals.index_of("issuerPk", 1), Some(16));
+        assert_eq!(signals.index_of("issuerPk", 2), None);
+        assert_eq!(signals.index_of("unknown", 0), None);
+    }
+
+    #[test]
+    fn test_constraints() {
+        let value = num_to_bytes(18);
+        assert!(SignalConstraint::eq(18).is_satisfied(&value));
+        assert!(SignalConstraint::gte(18).is_satisfied(&value));
+        assert!(!SignalConstraint::gte(19).is_satisfied(&value));
+        assert!(SignalConstraint::lte(18).is_satisfied(&value));
+        assert!(!SignalConstraint::lte(17).is_satisfied(&value));
+        assert!(SignalConstraint::range(10, 20).is_satisfied(&value));
+        assert!(!SignalConstraint::range(19, 20).is_satisfied(&value));
+        assert!(SignalConstraint::one_of(&[1, 18]).is_satisfied(&value));
+        assert!(!SignalConstraint::one_of(&[1, 2]).is_satisfied(&value));
+
+        let mut big = [0u8; 32];
+        big[0] = 1;
+        assert!(SignalConstraint::gte(u64::MAX).is_satisfied(&big));
+    }
+}
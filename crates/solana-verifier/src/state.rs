@@ -0,0 +1,658 @@
+/*
+ * This file is part of Albus code.
+ *
+ * Copyright (c) 2023, mFactory GmbH
+ *
+ * Albus is free software: you can redistribute it
+ * and/or modify it under the terms of the GNU Affero General Public License
+ * as published by the Free Software Foundation, either version 3
+ * of the License, or (at your option) any later version.
+ *
+ * Albus is distributed in the hope that it
+ * will be useful, but WITHOUT ANY WARRANTY; without even the implied
+ * warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.
+ * If not, see <https://www.gnu.org/licenses/agpl-3.0.html>.
+ *
+ * You can be released from the requirements of the Affero GNU General Public License
+ * by purchasing a commercial license. The purchase of such a license is
+ * mandatory as soon as you develop commercial activities using the
+ * Albus code without disclosing the source code of
+ * your own applications.
+ *
+ * The developer of this program can be contacted at <info@albus.finance>.
+ */
+
+use crate::constants::{
+    CIRCUIT_DISCRIMINATOR, COMPOSITE_PROOF_REQUEST_DISCRIMINATOR, ISSUER_DISCRIMINATOR,
+    POLICY_DISCRIMINATOR, PROOF_REQUEST_DISCRIMINATOR,
+};
+use crate::error::VerificationError;
+use crate::signals::Signals;
+use arrayref::{array_ref, array_refs};
+use solana_program::{msg, program_error::ProgramError, pubkey::Pubkey};
+
+const HEADER_LEN: usize = 218;
+const PROOF_DATA_LEN: usize = 256;
+
+/// Zero-copy view of the Albus `ProofRequest` account.
+///
+/// The proof and public inputs are borrowed from the account data,
+/// all other fields are decoded on creation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofRequestView<'a> {
+    /// The service provider associated with this request
+    pub service_provider: Pubkey,
+    /// The policy associated with this request
+    pub policy: Pubkey,
+    /// The circuit used for proof generation
+    pub circuit: Pubkey,
+    /// The issuer used for proof generation
+    pub issuer: Pubkey,
+    /// Proof request creator
+    pub owner: Pubkey,
+    /// Auto-increment service specific identifier
+    pub identifier: u64,
+    /// Timestamp for when the request was created
+    pub created_at: i64,
+    /// Timestamp for when the request expires
+    pub expired_at: i64,
+    /// Timestamp for when the `proof` was verified
+    pub verified_at: i64,
+    /// Timestamp for when the user was added the `proof`
+    pub proved_at: i64,
+    /// Timestamp indicating when the data will no longer be stored
+    pub retention_end_date: i64,
+    /// Status of the request
+    pub status: ProofRequestStatus,
+    /// PDA bump
+    pub bump: u8,
+    /// Proof payload
+    pub proof: Option<ProofDataView<'a>>,
+    /// Raw public inputs data (32 bytes per input)
+    public_inputs: &'a [u8],
+    /// The circuit version the `proof` was created with
+    pub circuit_version: u32,
+    /// Slot in which the `proof` was verified
+    pub verified_slot: u64,
+}
+
+impl<'a> ProofRequestView<'a> {
+    /// Version of the account layout supported by the decoder.
+    /// Version `1` is the layout before the `circuit_version` was appended,
+    /// version `2` before the `verified_slot` was appended.
+    pub const VERSION: u8 = 3;
+    /// Size of the fixed part of the account (up to the `proof` field)
+    pub const HEADER_LEN: usize = HEADER_LEN;
+    /// Size of the account allocated without public inputs
+    pub const BASE_LEN: usize = HEADER_LEN + 1 + PROOF_DATA_LEN + 4 + 4 + 8;
+
+    /// Detects the layout version of the account data by its size.
+    /// Accounts are allocated with room for the proof and 32 bytes per public input.
+    pub fn layout_version(data_len: usize) -> Option<u8> {
+        let fits =
+            |base_len: usize| matches!(data_len.checked_sub(base_len), Some(len) if len % 32 == 0);
+        if fits(Self::BASE_LEN) {
+            Some(Self::VERSION)
+        } else if fits(Self::BASE_LEN - 8) {
+            Some(2)
+        } else if fits(Self::BASE_LEN - 12) {
+            Some(1)
+        } else {
+            None
+        }
+    }
+
+    /// Decodes the proof request from the raw account data
+    pub fn try_from_slice(data: &'a [u8]) -> Result<Self, ProgramError> {
+        if Self::layout_version(data.len()) != Some(Self::VERSION) {
+            msg!("AlbusVerifierError: Unsupported proof request layout");
+            return Err(VerificationError::UnsupportedLayout.into());
+        }
+
+        let (header, rest) = data.split_at(Self::HEADER_LEN);
+        let header = array_ref![header, 0, HEADER_LEN];
+
+        #[rustfmt::skip]
+        let (
+            discriminator, service_provider, policy, circuit, issuer, owner, identifier,
+            created_at, expired_at, verified_at, proved_at, retention_end_date, [status], [bump],
+        ) = array_refs![header, 8, 32, 32, 32, 32, 32, 8, 8, 8, 8, 8, 8, 1, 1];
+
+        if discriminator != PROOF_REQUEST_DISCRIMINATOR {
+            msg!("AlbusVerifierError: Invalid proof request discriminator");
+            return Err(VerificationError::InvalidDiscriminator.into());
+        }
+
+        let (proof, rest) = match rest.split_first() {
+            Some((0, rest)) => (None, rest),
+            Some((1, rest)) if rest.len() >= ProofDataView::LEN => {
+                let (proof, rest) = rest.split_at(ProofDataView::LEN);
+                (
+                    Some(ProofDataView::new(array_ref![proof, 0, PROOF_DATA_LEN])),
+                    rest,
+                )
+            }
+            _ => {
+                msg!("AlbusVerifierError: Invalid proof request proof data");
+                return Err(VerificationError::InvalidAccountData.into());
+            }
+        };
+
+        let public_inputs = rest
+            .get(..4)
+            .map(|len| u32::from_le_bytes(*array_ref![len, 0, 4]) as usize)
+            .and_then(|len| rest.get(4..4 + len.checked_mul(32)?))
+            .ok_or_else(|| {
+                msg!("AlbusVerifierError: Invalid proof request public inputs");
+                VerificationError::InvalidAccountData
+            })?;
+
+        let offset = 4 + public_inputs.len();
+        let circuit_version = rest
+            .get(offset..offset + 4)
+            .map(|version| u32::from_le_bytes(*array_ref![version, 0, 4]))
+            .ok_or_else(|| {
+                msg!("AlbusVerifierError: Invalid proof request circuit version");
+                VerificationError::InvalidAccountData
+            })?;
+
+        let offset = offset + 4;
+        let verified_slot = rest
+            .get(offset..offset + 8)
+            .map(|slot| u64::from_le_bytes(*array_ref![slot, 0, 8]))
+            .ok_or_else(|| {
+                msg!("AlbusVerifierError: Invalid proof request verified slot");
+                VerificationError::InvalidAccountData
+            })?;
+
+        Ok(Self {
+            service_provider: Pubkey::new_from_array(*service_provider),
+            policy: Pubkey::new_from_array(*policy),
+            circuit: Pubkey::new_from_array(*circuit),
+            issuer: Pubkey::new_from_array(*issuer),
+            owner: Pubkey::new_from_array(*owner),
+            identifier: u64::from_le_bytes(*identifier),
+            created_at: i64::from_le_bytes(*created_at),
+            expired_at: i64::from_le_bytes(*expired_at),
+            verified_at: i64::from_le_bytes(*verified_at),
+            proved_at: i64::from_le_bytes(*proved_at),
+            retention_end_date: i64::from_le_bytes(*retention_end_date),
+            status: ProofRequestStatus::try_from(*status)?,
+            bump: *bump,
+            proof,
+            public_inputs,
+            circuit_version,
+            verified_slot,
+        })
+    }
+
+    /// Number of public inputs
+    #[inline]
+    pub fn public_inputs_len(&self) -> usize {
+        self.public_inputs.len() / 32
+    }
+
+    /// Returns the public input (scalar field, big-endian) at the given index
+    #[inline]
+    pub fn public_input(&self, index: usize) -> Option<&'a [u8; 32]> {
+        let offset = index.checked_mul(32)?;
+        self.public_inputs
+            .get(offset..offset + 32)
+            .map(|bytes| array_ref![bytes, 0, 32])
+    }
+
+    /// Returns the public input at the given index as a number.
+    /// Only the last 8 bytes are taken into account.
+    #[inline]
+    pub fn public_input_num(&self, index: usize) -> Option<u64> {
+        self.public_input(index)
+            .map(|bytes| u64::from_be_bytes(*array_ref![bytes, 24, 8]))
+    }
+
+    /// Iterates over the public inputs
+    pub fn public_inputs(&self) -> impl Iterator<Item = &'a [u8; 32]> {
+        self.public_inputs
+            .chunks_exact(32)
+            .map(|bytes| array_ref![bytes, 0, 32])
+    }
+}
+
+/// Zero-copy view of the Groth16 proof stored in the `ProofRequest` account
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProofDataView<'a> {
+    pub a: &'a [u8; 64],
+    pub b: &'a [u8; 128],
+    pub c: &'a [u8; 64],
+}
+
+impl<'a> ProofDataView<'a> {
+    pub const LEN: usize = PROOF_DATA_LEN;
+
+    fn new(data: &'a [u8; PROOF_DATA_LEN]) -> Self {
+        let (a, b, c) = array_refs![data, 64, 128, 64];
+        Self { a, b, c }
+    }
+}
+
+#[repr(u8)]
+#[derive(Default, Debug, Eq, PartialEq, Clone, Copy)]
+pub enum ProofRequestStatus {
+    #[default]
+    Pending,
+    Proved,
+    Verified,
+    Rejected,
+}
+
+impl TryFrom<u8> for ProofRequestStatus {
+    type Error = ProgramError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Pending),
+            1 => Ok(Self::Proved),
+            2 => Ok(Self::Verified),
+            3 => Ok(Self::Rejected),
+            _ => Err(VerificationError::InvalidAccountData.into()),
+        }
+    }
+}
+
+/// View of the Albus `CompositeProofRequest` account,
+/// which aggregates one `ProofRequest` per sub-policy of a composite policy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompositeProofRequestView {
+    /// The service provider associated with this request
+    pub service_provider: Pubkey,
+    /// The composite policy associated with this request
+    pub composite_policy: Pubkey,
+    /// Proof request creator
+    pub owner: Pubkey,
+    /// Timestamp for when the request was created
+    pub created_at: i64,
+    /// Earliest expiration of the component requests
+    pub expired_at: i64,
+    /// Timestamp for when the last component request was verified
+    pub verified_at: i64,
+    /// Aggregated status of the component requests
+    pub status: ProofRequestStatus,
+    /// PDA bump
+    pub bump: u8,
+    /// Component proof requests, in the order of the sub-policies
+    pub proof_requests: Vec<Pubkey>,
+}
+
+impl CompositeProofRequestView {
+    /// Returns `true` if the data belongs to a composite proof request
+    pub fn is_composite(data: &[u8]) -> bool {
+        data.get(..8) == Some(COMPOSITE_PROOF_REQUEST_DISCRIMINATOR)
+    }
+
+    /// Decodes the composite proof request from the raw account data
+    pub fn try_from_slice(data: &[u8]) -> Result<Self, ProgramError> {
+        let mut r = Reader::new(data);
+
+        if r.read_bytes(8)? != COMPOSITE_PROOF_REQUEST_DISCRIMINATOR {
+            msg!("AlbusVerifierError: Invalid composite proof request discriminator");
+            return Err(VerificationError::InvalidDiscriminator.into());
+        }
+
+        Ok(Self {
+            service_provider: r.read_pubkey()?,
+            composite_policy: r.read_pubkey()?,
+            owner: r.read_pubkey()?,
+            created_at: r.read_i64()?,
+            expired_at: r.read_i64()?,
+            verified_at: r.read_i64()?,
+            status: ProofRequestStatus::try_from(r.read_u8()?)?,
+            bump: r.read_u8()?,
+            proof_requests: {
+                let len = r.read_u32()? as usize;
+                let mut keys = Vec::with_capacity(len.min(r.data.len() / 32));
+                for _ in 0..len {
+                    keys.push(r.read_pubkey()?);
+                }
+                keys
+            },
+        })
+    }
+}
+
+/// Zero-copy view of the Albus `Circuit` account
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CircuitView<'a> {
+    /// Uniq code of the circuit
+    pub code: &'a str,
+    /// Name of the circuit
+    pub name: &'a str,
+    /// Short description
+    pub description: &'a str,
+    pub wasm_uri: &'a str,
+    pub zkey_uri: &'a str,
+    /// Creation date
+    pub created_at: i64,
+    /// PDA bump
+    pub bump: u8,
+    /// Verification key
+    pub vk: VerificationKeyView<'a>,
+    /// Output signals associated with the circuit
+    pub outputs: Vec<&'a str>,
+    /// Public signals associated with the circuit
+    pub public_signals: Vec<&'a str>,
+    /// Private signals associated with the circuit
+    pub private_signals: Vec<&'a str>,
+    /// SHA-256 of the `.wasm` file at `wasm_uri`
+    pub wasm_hash: &'a [u8; 32],
+    /// SHA-256 of the `.zkey` file at `zkey_uri`
+    pub zkey_hash: &'a [u8; 32],
+    /// SHA-256 of the verification key (`alpha || beta || gamma || delta || ic`)
+    pub vk_hash: &'a [u8; 32],
+    /// Latest published circuit version, `0` if none
+    pub latest_version: u32,
+    /// Status of the staged verification key
+    pub status: CircuitStatus,
+}
+
+impl<'a> CircuitView<'a> {
+    /// Decodes the circuit from the raw account data
+    pub fn try_from_slice(data: &'a [u8]) -> Result<Self, ProgramError> {
+        let mut r = Reader::new(data);
+
+        if r.read_bytes(8)? != CIRCUIT_DISCRIMINATOR {
+            msg!("AlbusVerifierError: Invalid circuit discriminator");
+            return Err(VerificationError::InvalidDiscriminator.into());
+        }
+
+        Ok(Self {
+            code: r.read_str()?,
+            name: r.read_str()?,
+            description: r.read_str()?,
+            wasm_uri: r.read_str()?,
+            zkey_uri: r.read_str()?,
+            created_at: r.read_i64()?,
+            bump: r.read_u8()?,
+            vk: VerificationKeyView {
+                alpha: r.read_array()?,
+                beta: r.read_array()?,
+                gamma: r.read_array()?,
+                delta: r.read_array()?,
+                ic: {
+                    let len = r.read_u32()? as usize;
+                    r.read_bytes(len.saturating_mul(64))?
+                },
+            },
+            outputs: r.read_str_vec()?,
+            public_signals: r.read_str_vec()?,
+            private_signals: r.read_str_vec()?,
+            wasm_hash: r.read_array()?,
+            zkey_hash: r.read_array()?,
+            vk_hash: r.read_array()?,
+            latest_version: r.read_u32()?,
+            status: CircuitStatus::try_from(r.read_u8()?)?,
+        })
+    }
+
+    /// Public signals (outputs first) of the circuit,
+    /// in the same order as the proof request public inputs
+    pub fn signals(&self) -> Signals {
+        let mut vec = Vec::with_capacity(self.outputs.len() + self.public_signals.len());
+        vec.extend_from_slice(self.outputs.as_slice());
+        vec.extend_from_slice(self.public_signals.as_slice());
+        Signals::new(&vec)
+    }
+}
+
+#[repr(u8)]
+#[derive(Default, Debug, Eq, PartialEq, Clone, Copy)]
+pub enum CircuitStatus {
+    /// The verification key is being uploaded
+    #[default]
+    Draft,
+    /// The verification key is validated and can be published
+    Finalized,
+}
+
+impl TryFrom<u8> for CircuitStatus {
+    type Error = ProgramError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Draft),
+            1 => Ok(Self::Finalized),
+            _ => Err(VerificationError::InvalidAccountData.into()),
+        }
+    }
+}
+
+/// Zero-copy view of the Albus `Issuer` account
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssuerView<'a> {
+    /// Signing public key
+    pub pubkey: Pubkey,
+    /// Signing public key in zk format (BJJ Point)
+    pub zk_pubkey: &'a [u8; 64],
+    /// The authority of the issuer that manages the issuer
+    pub authority: Pubkey,
+    /// Issuer status
+    pub is_disabled: bool,
+    /// Creation date
+    pub created_at: i64,
+    /// PDA bump
+    pub bump: u8,
+    /// Uniq code of the issuer
+    pub code: &'a str,
+    /// The name of the issuer
+    pub name: &'a str,
+    /// Short description
+    pub description: &'a str,
+}
+
+impl<'a> IssuerView<'a> {
+    /// Decodes the issuer from the raw account data
+    pub fn try_from_slice(data: &'a [u8]) -> Result<Self, ProgramError> {
+        let mut r = Reader::new(data);
+
+        if r.read_bytes(8)? != ISSUER_DISCRIMINATOR {
+            msg!("AlbusVerifierError: Invalid issuer discriminator");
+            return Err(VerificationError::InvalidDiscriminator.into());
+        }
+
+        Ok(Self {
+            pubkey: r.read_pubkey()?,
+            zk_pubkey: r.read_array()?,
+            authority: r.read_pubkey()?,
+            is_disabled: r.read_u8()? != 0,
+            created_at: r.read_i64()?,
+            bump: r.read_u8()?,
+            code: r.read_str()?,
+            name: r.read_str()?,
+            description: r.read_str()?,
+        })
+    }
+}
+
+/// Zero-copy view of the Albus `Policy` account
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyView<'a> {
+    /// The service provider this belongs to
+    pub service_provider: Pubkey,
+    /// The circuit associated with this policy
+    pub circuit: Pubkey,
+    /// Unique code of the policy (associated with the service)
+    pub code: &'a str,
+    /// Name of the policy
+    pub name: &'a str,
+    /// Short description
+    pub description: &'a str,
+    /// Request expiration period in seconds
+    pub expiration_period: u32,
+    /// Request retention period in seconds
+    pub retention_period: u32,
+    /// Total number of proof requests
+    pub proof_request_count: u64,
+    /// Creation date
+    pub created_at: i64,
+    /// PDA bump
+    pub bump: u8,
+    /// Policy rules
+    pub rules: Vec<PolicyRuleView<'a>>,
+    /// Pinned circuit version, `0` follows the latest version of the circuit
+    pub circuit_version: u32,
+    /// Signal holding the credential nullifier, empty if nullifiers are not enforced
+    pub nullifier_signal: &'a str,
+}
+
+impl<'a> PolicyView<'a> {
+    /// Decodes the policy from the raw account data
+    pub fn try_from_slice(data: &'a [u8]) -> Result<Self, ProgramError> {
+        let mut r = Reader::new(data);
+
+        if r.read_bytes(8)? != POLICY_DISCRIMINATOR {
+            msg!("AlbusVerifierError: Invalid policy discriminator");
+            return Err(VerificationError::InvalidDiscriminator.into());
+        }
+
+        Ok(Self {
+            service_provider: r.read_pubkey()?,
+            circuit: r.read_pubkey()?,
+            code: r.read_str()?,
+            name: r.read_str()?,
+            description: r.read_str()?,
+            expiration_period: r.read_u32()?,
+            retention_period: r.read_u32()?,
+            proof_request_count: r.read_u64()?,
+            created_at: r.read_i64()?,
+            bump: r.read_u8()?,
+            rules: {
+                let len = r.read_u32()? as usize;
+                let mut rules = Vec::with_capacity(len.min(r.data.len() / 40));
+                for _ in 0..len {
+                    rules.push(PolicyRuleView {
+                        key: r.read_str()?,
+                        value: r.read_array()?,
+                        label: r.read_str()?,
+                        operator: PolicyRuleOperator::try_from(r.read_u8()?)?,
+                    });
+                }
+                rules
+            },
+            circuit_version: r.read_u32()?,
+            nullifier_signal: r.read_str()?,
+        })
+    }
+}
+
+/// Zero-copy view of the policy rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PolicyRuleView<'a> {
+    pub key: &'a str,
+    /// Scalar Field
+    pub value: &'a [u8; 32],
+    pub label: &'a str,
+    /// How the public input is compared to the `value`
+    pub operator: PolicyRuleOperator,
+}
+
+#[repr(u8)]
+#[derive(Default, Debug, Eq, PartialEq, Clone, Copy)]
+pub enum PolicyRuleOperator {
+    #[default]
+    Eq,
+    Gte,
+    Lte,
+    InSet,
+    MerkleRoot,
+}
+
+impl TryFrom<u8> for PolicyRuleOperator {
+    type Error = ProgramError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Eq),
+            1 => Ok(Self::Gte),
+            2 => Ok(Self::Lte),
+            3 => Ok(Self::InSet),
+            4 => Ok(Self::MerkleRoot),
+            _ => Err(VerificationError::InvalidAccountData.into()),
+        }
+    }
+}
+
+/// Zero-copy view of the circuit verification key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerificationKeyView<'a> {
+    pub alpha: &'a [u8; 64],
+    pub beta: &'a [u8; 128],
+    pub gamma: &'a [u8; 128],
+    pub delta: &'a [u8; 128],
+    /// Raw `ic` points data (64 bytes per point)
+    pub ic: &'a [u8],
+}
+
+/// Minimal borsh reader used to decode variable-length accounts
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], ProgramError> {
+        if self.data.len() < len {
+            msg!("AlbusVerifierError: Unexpected end of account data");
+            return Err(VerificationError::InvalidAccountData.into());
+        }
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(bytes)
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<&'a [u8; N], ProgramError> {
+        self.read_bytes(N)?
+            .try_into()
+            .map_err(|_| VerificationError::InvalidAccountData.into())
+    }
+
+    fn read_u8(&mut self) -> Result<u8, ProgramError> {
+        Ok(self.read_array::<1>()?[0])
+    }
+
+    fn read_u32(&mut self) -> Result<u32, ProgramError> {
+        Ok(u32::from_le_bytes(*self.read_array()?))
+    }
+
+    fn read_u64(&mut self) -> Result<u64, ProgramError> {
+        Ok(u64::from_le_bytes(*self.read_array()?))
+    }
+
+    fn read_i64(&mut self) -> Result<i64, ProgramError> {
+        Ok(i64::from_le_bytes(*self.read_array()?))
+    }
+
+    fn read_pubkey(&mut self) -> Result<Pubkey, ProgramError> {
+        Ok(Pubkey::new_from_array(*self.read_array()?))
+    }
+
+    fn read_str(&mut self) -> Result<&'a str, ProgramError> {
+        let len = self.read_u32()? as usize;
+        core::str::from_utf8(self.read_bytes(len)?)
+            .map_err(|_| VerificationError::InvalidAccountData.into())
+    }
+
+    fn read_str_vec(&mut self) -> Result<Vec<&'a str>, ProgramError> {
+        let len = self.read_u32()? as usize;
+        // each string takes at least 4 bytes, avoid huge allocations on malformed data
+        let mut vec = Vec::with_capacity(len.min(self.data.len() / 4));
+        for _ in 0..len {
+            vec.push(self.read_str()?);
+        }
+        Ok(vec)
+    }
+}
@@ -28,7 +28,7 @@
 
 #![allow(dead_code)]
 
-use crate::constants::{ALBUS_PROGRAM_ID, POLICY_DISCRIMINATOR, PROOF_REQUEST_DISCRIMINATOR};
+use crate::constants::{POLICY_DISCRIMINATOR, PROOF_REQUEST_DISCRIMINATOR};
 use arrayref::array_ref;
 use solana_program::account_info::AccountInfo;
 use solana_program::program_memory::sol_memcmp;
@@ -40,23 +40,31 @@ pub fn cmp_pubkeys(a: impl AsRef<[u8]>, b: impl AsRef<[u8]>) -> bool {
 }
 
 /// Generates the service provider program address for Albus Protocol
-pub fn find_service_provider_address(code: &str) -> (Pubkey, u8) {
-    Pubkey::find_program_address(&[b"service-provider", code.as_bytes()], &ALBUS_PROGRAM_ID)
+pub fn find_service_provider_address(program_id: &Pubkey, code: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"service-provider", code.as_bytes()], program_id)
 }
 
 /// Generates the policy program address for Albus Protocol
-pub fn find_policy_address(service_provider: &Pubkey, code: &str) -> (Pubkey, u8) {
+pub fn find_policy_address(
+    program_id: &Pubkey,
+    service_provider: &Pubkey,
+    code: &str,
+) -> (Pubkey, u8) {
     Pubkey::find_program_address(
         &[b"policy", service_provider.as_ref(), code.as_bytes()],
-        &ALBUS_PROGRAM_ID,
+        program_id,
     )
 }
 
 /// Generates the proof request program address for the Albus protocol
-pub fn find_proof_request_address(policy: &Pubkey, user: &Pubkey) -> (Pubkey, u8) {
+pub fn find_proof_request_address(
+    program_id: &Pubkey,
+    policy: &Pubkey,
+    user: &Pubkey,
+) -> (Pubkey, u8) {
     Pubkey::find_program_address(
         &[b"proof-request", policy.as_ref(), user.as_ref()],
-        &ALBUS_PROGRAM_ID,
+        program_id,
     )
 }
 
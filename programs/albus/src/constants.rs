@@ -43,6 +43,9 @@ pub const META_VALID_UNTIL_SIGNAL: &str = "meta_validUntil";
 /// Issuer signal name used in Albus circuits
 pub const ISSUER_PK_SIGNAL: &str = "issuerPk";
 
+/// Revocation registry root signal name used in Albus circuits
+pub const REVOCATION_ROOT_SIGNAL: &str = "revocationRoot";
+
 /// Timestamp signal name used in Albus circuits
 pub const TIMESTAMP_SIGNAL: &str = "timestamp";
 pub const TIMESTAMP_THRESHOLD: u16 = 60;
@@ -48,4 +48,34 @@ pub enum AlbusError {
     ProofVerificationFailed,
     #[msg("Invalid public inputs")]
     InvalidPublicInputs,
+    #[msg("Invalid circuit version")]
+    InvalidCircuitVersion,
+    #[msg("Circuit version is deprecated")]
+    DeprecatedCircuitVersion,
+    #[msg("Circuit is not finalized")]
+    CircuitNotFinalized,
+    #[msg("Invalid verification key")]
+    InvalidVerificationKey,
+    #[msg("Policy rule signal not found")]
+    PolicyRuleSignalNotFound,
+    #[msg("Policy rule has more values than the signal size")]
+    PolicyRuleTooManyValues,
+    #[msg("Policy rule failed: value is not equal")]
+    PolicyRuleNotEqual,
+    #[msg("Policy rule failed: value is too low")]
+    PolicyRuleTooLow,
+    #[msg("Policy rule failed: value is too high")]
+    PolicyRuleTooHigh,
+    #[msg("Policy rule failed: value is not in the allowed set")]
+    PolicyRuleNotInSet,
+    #[msg("Policy rule failed: invalid Merkle root")]
+    PolicyRuleInvalidMerkleRoot,
+    #[msg("Invalid nullifier")]
+    InvalidNullifier,
+    #[msg("Nullifier has already been used")]
+    NullifierAlreadyUsed,
+    #[msg("Invalid revocation registry")]
+    InvalidRevocationRegistry,
+    #[msg("Account is not in a legacy layout")]
+    NotLegacyAccount,
 }
@@ -26,9 +26,55 @@
  * The developer of this program can be contacted at <info@albus.finance>.
  */
 
-use crate::state::RevelationStatus;
+use crate::state::{ProofRequestStatus, RevelationStatus};
 use crate::*;
 
+#[event]
+pub struct UpdateCircuitHashesEvent {
+    #[index]
+    pub circuit: Pubkey,
+    pub wasm_hash: [u8; 32],
+    pub zkey_hash: [u8; 32],
+    pub vk_hash: [u8; 32],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FinalizeCircuitEvent {
+    #[index]
+    pub circuit: Pubkey,
+    pub vk_hash: [u8; 32],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CreateCircuitVersionEvent {
+    #[index]
+    pub circuit: Pubkey,
+    pub circuit_version: Pubkey,
+    pub version: u32,
+    pub vk_hash: [u8; 32],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DeprecateCircuitVersionEvent {
+    #[index]
+    pub circuit: Pubkey,
+    pub circuit_version: Pubkey,
+    pub version: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UpdateRevocationRootEvent {
+    #[index]
+    pub issuer: Pubkey,
+    pub revocation_registry: Pubkey,
+    pub root: [u8; 32],
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct CreateProofRequestEvent {
     #[index]
@@ -46,6 +92,26 @@ pub struct DeleteProofRequestEvent {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct CreateCompositeProofRequestEvent {
+    #[index]
+    pub service_provider: Pubkey,
+    pub composite_policy: Pubkey,
+    pub owner: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SyncCompositeProofRequestEvent {
+    #[index]
+    pub composite_proof_request: Pubkey,
+    #[index]
+    pub service_provider: Pubkey,
+    pub owner: Pubkey,
+    pub status: ProofRequestStatus,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct ProveEvent {
     #[index]
@@ -0,0 +1,285 @@
+/*
+ * This file is part of Albus code.
+ *
+ * Copyright (c) 2023, mFactory GmbH
+ *
+ * Albus is free software: you can redistribute it
+ * and/or modify it under the terms of the GNU Affero General Public License
+ * as published by the Free Software Foundation, either version 3
+ * of the License, or (at your option) any later version.
+ *
+ * Albus is distributed in the hope that it
+ * will be useful, but WITHOUT ANY WARRANTY; without even the implied
+ * warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.
+ * If not, see <https://www.gnu.org/licenses/agpl-3.0.html>.
+ *
+ * You can be released from the requirements of the Affero GNU General Public License
+ * by purchasing a commercial license. The purchase of such a license is
+ * mandatory as soon as you develop commercial activities using the
+ * Albus code without disclosing the source code of
+ * your own applications.
+ *
+ * The developer of this program can be contacted at <info@albus.finance>.
+ */
+
+use anchor_lang::{prelude::*, system_program, Discriminator};
+
+use crate::errors::AlbusError;
+use crate::state::{
+    Circuit, LegacyCircuit, LegacyPolicy, LegacyPolicyRule, LegacyProofRequest,
+    LegacyServiceProvider, Policy, PolicyRule, ProofRequest, ServiceProvider,
+};
+use crate::utils::{assert_authorized, cmp_pubkeys};
+use crate::ID;
+
+/// Converts a [Circuit], [Policy], [ProofRequest] or [ServiceProvider] created before fields
+/// were appended to it to the current layout. The account is grown to the current size and its
+/// rent is topped up by the authority. Accounts that are already in the current layout are rejected.
+pub fn handler(ctx: Context<AdminMigrateAccount>) -> Result<()> {
+    assert_authorized(ctx.accounts.authority.key)?;
+
+    let account = ctx.accounts.account.to_account_info();
+    if !cmp_pubkeys(account.owner, &ID) {
+        msg!("Error: Account is not owned by the program");
+        return Err(AlbusError::InvalidOwner.into());
+    }
+
+    let (data, len) = migrate(&account.try_borrow_data()?)?;
+
+    let lamports = Rent::get()?
+        .minimum_balance(len)
+        .saturating_sub(account.lamports());
+    if lamports > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: account.clone(),
+                },
+            ),
+            lamports,
+        )?;
+    }
+
+    account.realloc(len, false)?;
+
+    // stale bytes past the legacy data must not be read as the appended fields
+    let mut dst = account.try_borrow_mut_data()?;
+    dst.fill(0);
+    dst[..data.len()].copy_from_slice(&data);
+
+    Ok(())
+}
+
+/// Returns the account data in the current layout and the new account size
+fn migrate(data: &[u8]) -> Result<(Vec<u8>, usize)> {
+    if data.len() < 8 {
+        return Err(AlbusError::NotLegacyAccount.into());
+    }
+    let (discriminator, mut body) = data.split_at(8);
+    let mut res = vec![];
+
+    if discriminator == Circuit::DISCRIMINATOR {
+        let legacy = LegacyCircuit::deserialize(&mut body)?;
+        let public_len = Circuit::signals_count(&legacy.outputs)
+            + Circuit::signals_count(&legacy.public_signals);
+        let private_len = Circuit::signals_count(&legacy.private_signals);
+        if data.len() != LegacyCircuit::space(public_len, private_len) {
+            return Err(not_legacy());
+        }
+        Circuit::from(legacy).try_serialize(&mut res)?;
+        return Ok((res, Circuit::space(public_len, private_len)));
+    }
+
+    if discriminator == Policy::DISCRIMINATOR {
+        let legacy = LegacyPolicy::deserialize(&mut body)?;
+        let rules_len = capacity(
+            data.len(),
+            LegacyPolicy::space(0),
+            LegacyPolicyRule::INIT_SPACE,
+        )
+        .filter(|c| *c >= legacy.rules.len())
+        .ok_or_else(not_legacy)?;
+        // both sizes match for some capacities, the current layout must not decode then
+        if capacity(data.len(), Policy::space(0), PolicyRule::INIT_SPACE).is_some()
+            && Policy::try_deserialize(&mut &data[..]).is_ok()
+        {
+            return Err(not_legacy());
+        }
+        Policy::from(legacy).try_serialize(&mut res)?;
+        return Ok((res, Policy::space(rules_len)));
+    }
+
+    if discriminator == ProofRequest::DISCRIMINATOR {
+        let legacy = LegacyProofRequest::deserialize(&mut body)?;
+        let inputs_len = capacity(data.len(), LegacyProofRequest::space(0), 32)
+            .filter(|c| *c >= legacy.public_inputs.len())
+            .and_then(|c| u8::try_from(c).ok())
+            .ok_or_else(not_legacy)?;
+        ProofRequest::from(legacy).try_serialize(&mut res)?;
+        return Ok((res, ProofRequest::space(inputs_len)));
+    }
+
+    if discriminator == ServiceProvider::DISCRIMINATOR {
+        if data.len() != LegacyServiceProvider::space() {
+            return Err(not_legacy());
+        }
+        let legacy = LegacyServiceProvider::deserialize(&mut body)?;
+        ServiceProvider::from(legacy).try_serialize(&mut res)?;
+        return Ok((res, ServiceProvider::space()));
+    }
+
+    msg!("Error: Unsupported account type");
+    Err(AlbusError::NotLegacyAccount.into())
+}
+
+/// Number of items of an account of `len` bytes with `base` bytes and items of `item_len` bytes
+fn capacity(len: usize, base: usize, item_len: usize) -> Option<usize> {
+    let items_len = len.checked_sub(base)?;
+    (items_len % item_len == 0).then_some(items_len / item_len)
+}
+
+fn not_legacy() -> Error {
+    msg!("Error: Account size does not match the legacy layout");
+    AlbusError::NotLegacyAccount.into()
+}
+
+#[derive(Accounts)]
+pub struct AdminMigrateAccount<'info> {
+    /// CHECK: owner, type and layout are checked by the handler
+    #[account(mut)]
+    pub account: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::state::{ContactInfo, PolicyRuleOperator, ProofRequestStatus};
+
+    fn legacy_data<T: AnchorSerialize>(discriminator: [u8; 8], account: &T, len: usize) -> Vec<u8> {
+        let mut data = discriminator.to_vec();
+        account.serialize(&mut data).unwrap();
+        data.resize(len, 0);
+        data
+    }
+
+    #[test]
+    fn test_migrate_policy() {
+        let legacy = LegacyPolicy {
+            service_provider: Pubkey::new_unique(),
+            circuit: Pubkey::new_unique(),
+            code: "code".to_string(),
+            name: "name".to_string(),
+            description: "".to_string(),
+            expiration_period: 1,
+            retention_period: 2,
+            proof_request_count: 3,
+            created_at: 4,
+            bump: 5,
+            rules: vec![LegacyPolicyRule {
+                key: "age".to_string(),
+                value: [18; 32],
+                label: "".to_string(),
+            }],
+        };
+        let data = legacy_data(Policy::DISCRIMINATOR, &legacy, LegacyPolicy::space(2));
+
+        let (res, len) = migrate(&data).unwrap();
+        assert_eq!(len, Policy::space(2));
+
+        let policy = Policy::try_deserialize(&mut &res[..]).unwrap();
+        assert_eq!(policy.proof_request_count, 3);
+        assert_eq!(policy.circuit_version, 0);
+        assert_eq!(policy.rules.len(), 1);
+        assert_eq!(policy.rules[0].value, [18; 32]);
+        assert!(matches!(policy.rules[0].operator, PolicyRuleOperator::Eq));
+
+        let mut migrated = res;
+        migrated.resize(len, 0);
+        assert!(migrate(&migrated).is_err());
+    }
+
+    #[test]
+    fn test_migrate_proof_request() {
+        let legacy = LegacyProofRequest {
+            service_provider: Pubkey::new_unique(),
+            policy: Pubkey::new_unique(),
+            circuit: Pubkey::new_unique(),
+            issuer: Pubkey::new_unique(),
+            owner: Pubkey::new_unique(),
+            identifier: 1,
+            created_at: 2,
+            expired_at: 3,
+            verified_at: 0,
+            proved_at: 0,
+            retention_end_date: 0,
+            status: ProofRequestStatus::Pending,
+            bump: 4,
+            proof: None,
+            public_inputs: vec![[7; 32]],
+        };
+        let data = legacy_data(
+            ProofRequest::DISCRIMINATOR,
+            &legacy,
+            LegacyProofRequest::space(3),
+        );
+
+        let (res, len) = migrate(&data).unwrap();
+        assert_eq!(len, ProofRequest::space(3));
+
+        let req = ProofRequest::try_deserialize(&mut &res[..]).unwrap();
+        assert_eq!(req.identifier, 1);
+        assert_eq!(req.public_inputs, vec![[7; 32]]);
+        assert_eq!(req.circuit_version, 0);
+
+        let mut migrated = res;
+        migrated.resize(len, 0);
+        assert!(migrate(&migrated).is_err());
+
+        // the size must match the legacy allocation
+        assert!(migrate(&data[..data.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_migrate_service_provider() {
+        let legacy = LegacyServiceProvider {
+            authority: Pubkey::new_unique(),
+            code: "code".to_string(),
+            name: "name".to_string(),
+            website: "".to_string(),
+            contact_info: ContactInfo::default(),
+            proof_request_count: 1,
+            policy_count: 2,
+            created_at: 3,
+            bump: 4,
+            secret_share_threshold: 0,
+            trustees: vec![],
+        };
+        let data = legacy_data(
+            ServiceProvider::DISCRIMINATOR,
+            &legacy,
+            LegacyServiceProvider::space(),
+        );
+
+        let (res, len) = migrate(&data).unwrap();
+        assert_eq!(len, ServiceProvider::space());
+
+        let service = ServiceProvider::try_deserialize(&mut &res[..]).unwrap();
+        assert_eq!(service.policy_count, 2);
+        assert_eq!(service.composite_policy_count, 0);
+
+        let mut migrated = res;
+        migrated.resize(len, 0);
+        assert!(migrate(&migrated).is_err());
+    }
+}
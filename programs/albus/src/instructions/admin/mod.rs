@@ -27,6 +27,7 @@
  */
 
 pub mod close_account;
+pub mod migrate_account;
 pub mod withdraw;
 
-pub use self::{close_account::*, withdraw::*};
+pub use self::{close_account::*, migrate_account::*, withdraw::*};
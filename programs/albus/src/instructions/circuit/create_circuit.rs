@@ -28,7 +28,8 @@
 
 use anchor_lang::prelude::*;
 
-use crate::state::Circuit;
+use crate::events::UpdateCircuitHashesEvent;
+use crate::state::{Circuit, CircuitStatus};
 use crate::utils::assert_authorized;
 
 pub fn handler(ctx: Context<CreateCircuit>, data: CreateCircuitData) -> Result<()> {
@@ -48,6 +49,18 @@ pub fn handler(ctx: Context<CreateCircuit>, data: CreateCircuitData) -> Result<(
     circuit.private_signals = data.private_signals;
     circuit.public_signals = data.public_signals;
     circuit.bump = ctx.bumps.circuit;
+    circuit.status = CircuitStatus::Draft;
+    circuit.wasm_hash = data.wasm_hash;
+    circuit.zkey_hash = data.zkey_hash;
+    circuit.vk_hash = circuit.vk.hash();
+
+    emit!(UpdateCircuitHashesEvent {
+        circuit: circuit.key(),
+        wasm_hash: circuit.wasm_hash,
+        zkey_hash: circuit.zkey_hash,
+        vk_hash: circuit.vk_hash,
+        timestamp,
+    });
 
     Ok(())
 }
@@ -59,6 +72,8 @@ pub struct CreateCircuitData {
     pub description: String,
     pub wasm_uri: String,
     pub zkey_uri: String,
+    pub wasm_hash: [u8; 32],
+    pub zkey_hash: [u8; 32],
     pub outputs: Vec<String>,
     pub public_signals: Vec<String>,
     pub private_signals: Vec<String>,
@@ -0,0 +1,95 @@
+/*
+ * This file is part of Albus code.
+ *
+ * Copyright (c) 2023, mFactory GmbH
+ *
+ * Albus is free software: you can redistribute it
+ * and/or modify it under the terms of the GNU Affero General Public License
+ * as published by the Free Software Foundation, either version 3
+ * of the License, or (at your option) any later version.
+ *
+ * Albus is distributed in the hope that it
+ * will be useful, but WITHOUT ANY WARRANTY; without even the implied
+ * warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.
+ * If not, see <https://www.gnu.org/licenses/agpl-3.0.html>.
+ *
+ * You can be released from the requirements of the Affero GNU General Public License
+ * by purchasing a commercial license. The purchase of such a license is
+ * mandatory as soon as you develop commercial activities using the
+ * Albus code without disclosing the source code of
+ * your own applications.
+ *
+ * The developer of this program can be contacted at <info@albus.finance>.
+ */
+
+use anchor_lang::prelude::*;
+
+use crate::errors::AlbusError;
+use crate::events::CreateCircuitVersionEvent;
+use crate::state::{Circuit, CircuitVersion, CircuitVersionStatus};
+use crate::utils::assert_authorized;
+
+/// Publishes the staged verification key of the [Circuit] as a new immutable [CircuitVersion].
+pub fn handler(ctx: Context<CreateCircuitVersion>) -> Result<()> {
+    assert_authorized(&ctx.accounts.authority.key())?;
+
+    let circuit = &mut ctx.accounts.circuit;
+
+    if !circuit.is_finalized() {
+        msg!("Error: Circuit verification key is not finalized");
+        return Err(AlbusError::CircuitNotFinalized.into());
+    }
+
+    let timestamp = Clock::get()?.unix_timestamp;
+
+    circuit.latest_version += 1;
+
+    let version = &mut ctx.accounts.circuit_version;
+    version.circuit = circuit.key();
+    version.version = circuit.latest_version;
+    version.status = CircuitVersionStatus::Active;
+    version.wasm_hash = circuit.wasm_hash;
+    version.zkey_hash = circuit.zkey_hash;
+    version.vk_hash = circuit.vk.hash();
+    version.vk = circuit.vk.clone();
+    version.created_at = timestamp;
+    version.bump = ctx.bumps.circuit_version;
+
+    emit!(CreateCircuitVersionEvent {
+        circuit: circuit.key(),
+        circuit_version: version.key(),
+        version: version.version,
+        vk_hash: version.vk_hash,
+        timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateCircuitVersion<'info> {
+    #[account(mut)]
+    pub circuit: Box<Account<'info, Circuit>>,
+
+    #[account(
+        init,
+        seeds = [
+            CircuitVersion::SEED,
+            circuit.key().as_ref(),
+            &(circuit.latest_version + 1).to_le_bytes(),
+        ],
+        bump,
+        payer = authority,
+        space = CircuitVersion::space(circuit.vk.ic.len().saturating_sub(1))
+    )]
+    pub circuit_version: Box<Account<'info, CircuitVersion>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
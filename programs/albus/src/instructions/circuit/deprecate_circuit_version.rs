@@ -0,0 +1,67 @@
+/*
+ * This file is part of Albus code.
+ *
+ * Copyright (c) 2023, mFactory GmbH
+ *
+ * Albus is free software: you can redistribute it
+ * and/or modify it under the terms of the GNU Affero General Public License
+ * as published by the Free Software Foundation, either version 3
+ * of the License, or (at your option) any later version.
+ *
+ * Albus is distributed in the hope that it
+ * will be useful, but WITHOUT ANY WARRANTY; without even the implied
+ * warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.
+ * If not, see <https://www.gnu.org/licenses/agpl-3.0.html>.
+ *
+ * You can be released from the requirements of the Affero GNU General Public License
+ * by purchasing a commercial license. The purchase of such a license is
+ * mandatory as soon as you develop commercial activities using the
+ * Albus code without disclosing the source code of
+ * your own applications.
+ *
+ * The developer of this program can be contacted at <info@albus.finance>.
+ */
+
+use anchor_lang::prelude::*;
+
+use crate::events::DeprecateCircuitVersionEvent;
+use crate::state::{Circuit, CircuitVersion, CircuitVersionStatus};
+use crate::utils::assert_authorized;
+
+/// Deprecates the [CircuitVersion], no new proofs can be created with it.
+/// Already proved requests can still be verified.
+pub fn handler(ctx: Context<DeprecateCircuitVersion>) -> Result<()> {
+    assert_authorized(&ctx.accounts.authority.key())?;
+
+    let timestamp = Clock::get()?.unix_timestamp;
+
+    let version = &mut ctx.accounts.circuit_version;
+    version.status = CircuitVersionStatus::Deprecated;
+    version.deprecated_at = timestamp;
+
+    emit!(DeprecateCircuitVersionEvent {
+        circuit: version.circuit,
+        circuit_version: version.key(),
+        version: version.version,
+        timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DeprecateCircuitVersion<'info> {
+    pub circuit: Box<Account<'info, Circuit>>,
+
+    #[account(mut, has_one = circuit)]
+    pub circuit_version: Box<Account<'info, CircuitVersion>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,90 @@
+/*
+ * This file is part of Albus code.
+ *
+ * Copyright (c) 2023, mFactory GmbH
+ *
+ * Albus is free software: you can redistribute it
+ * and/or modify it under the terms of the GNU Affero General Public License
+ * as published by the Free Software Foundation, either version 3
+ * of the License, or (at your option) any later version.
+ *
+ * Albus is distributed in the hope that it
+ * will be useful, but WITHOUT ANY WARRANTY; without even the implied
+ * warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.
+ * If not, see <https://www.gnu.org/licenses/agpl-3.0.html>.
+ *
+ * You can be released from the requirements of the Affero GNU General Public License
+ * by purchasing a commercial license. The purchase of such a license is
+ * mandatory as soon as you develop commercial activities using the
+ * Albus code without disclosing the source code of
+ * your own applications.
+ *
+ * The developer of this program can be contacted at <info@albus.finance>.
+ */
+
+use anchor_lang::prelude::*;
+use groth16_solana::VK;
+
+use crate::errors::AlbusError;
+use crate::events::FinalizeCircuitEvent;
+use crate::state::{Circuit, CircuitStatus};
+use crate::utils::assert_authorized;
+
+/// Completes the verification key upload of the [Circuit].
+/// Validates the number of IC points against the circuit signals and the point encodings.
+pub fn handler(ctx: Context<FinalizeCircuit>) -> Result<()> {
+    assert_authorized(&ctx.accounts.authority.key())?;
+
+    let circuit = &mut ctx.accounts.circuit;
+
+    let expected_ic_len = circuit.public_inputs_count() + 1;
+    if circuit.vk.ic.len() != expected_ic_len {
+        msg!(
+            "Error: Invalid IC length {}, expected {}",
+            circuit.vk.ic.len(),
+            expected_ic_len
+        );
+        return Err(AlbusError::InvalidVerificationKey.into());
+    }
+
+    // points are validated regardless of `verify-on-chain`,
+    // clients verifying off-chain rely on a well-formed key as well
+    let vk = VK {
+        alpha: circuit.vk.alpha,
+        beta: circuit.vk.beta,
+        gamma: circuit.vk.gamma,
+        delta: circuit.vk.delta,
+        ic: &circuit.vk.ic,
+    };
+
+    vk.validate_points().map_err(|e| {
+        msg!("Groth16Verifier: {:?}", e);
+        AlbusError::InvalidVerificationKey
+    })?;
+
+    circuit.status = CircuitStatus::Finalized;
+    circuit.vk_hash = circuit.vk.hash();
+
+    emit!(FinalizeCircuitEvent {
+        circuit: circuit.key(),
+        vk_hash: circuit.vk_hash,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FinalizeCircuit<'info> {
+    #[account(mut)]
+    pub circuit: Box<Account<'info, Circuit>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -27,9 +27,15 @@
  */
 
 pub mod create_circuit;
+pub mod create_circuit_version;
 pub mod delete_circuit;
+pub mod deprecate_circuit_version;
+pub mod finalize_circuit;
 pub mod update_circuit_vk;
 
 pub use self::create_circuit::*;
+pub use self::create_circuit_version::*;
 pub use self::delete_circuit::*;
+pub use self::deprecate_circuit_version::*;
+pub use self::finalize_circuit::*;
 pub use self::update_circuit_vk::*;
@@ -28,9 +28,17 @@
 
 use anchor_lang::prelude::*;
 
-use crate::state::Circuit;
+use crate::errors::AlbusError;
+use crate::events::UpdateCircuitHashesEvent;
+use crate::state::{Circuit, CircuitStatus};
 use crate::utils::assert_authorized;
 
+/// Updates the staged verification key of the [Circuit].
+/// Proofs are verified against immutable [crate::state::CircuitVersion]s,
+/// the staged key is published with `create_circuit_version`.
+///
+/// Large keys are uploaded in chunks with `extend_ic`, the circuit stays `Draft`
+/// until the upload is validated by `finalize_circuit`.
 pub fn handler(ctx: Context<UpdateCircuitVk>, data: UpdateCircuitVkData) -> Result<()> {
     assert_authorized(&ctx.accounts.authority.key())?;
 
@@ -58,6 +66,38 @@ pub fn handler(ctx: Context<UpdateCircuitVk>, data: UpdateCircuitVkData) -> Resu
         } else {
             circuit.vk.ic = ic;
         }
+
+        if circuit.vk.ic.len() > circuit.public_inputs_count() + 1 {
+            msg!(
+                "Error: Too many IC points, expected {}",
+                circuit.public_inputs_count() + 1
+            );
+            return Err(AlbusError::InvalidVerificationKey.into());
+        }
+    }
+
+    if let Some(wasm_hash) = data.wasm_hash {
+        circuit.wasm_hash = wasm_hash;
+    }
+
+    if let Some(zkey_hash) = data.zkey_hash {
+        circuit.zkey_hash = zkey_hash;
+    }
+
+    let vk_hash = circuit.vk.hash();
+
+    if data.wasm_hash.is_some() || data.zkey_hash.is_some() || vk_hash != circuit.vk_hash {
+        circuit.vk_hash = vk_hash;
+        // the changes must be validated again before the next version
+        circuit.status = CircuitStatus::Draft;
+
+        emit!(UpdateCircuitHashesEvent {
+            circuit: circuit.key(),
+            wasm_hash: circuit.wasm_hash,
+            zkey_hash: circuit.zkey_hash,
+            vk_hash,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
     }
 
     Ok(())
@@ -71,6 +111,8 @@ pub struct UpdateCircuitVkData {
     pub delta: Option<[u8; 128]>,
     pub ic: Option<Vec<[u8; 64]>>,
     pub extend_ic: bool,
+    pub wasm_hash: Option<[u8; 32]>,
+    pub zkey_hash: Option<[u8; 32]>,
 }
 
 #[derive(Accounts)]
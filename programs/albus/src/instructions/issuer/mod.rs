@@ -27,7 +27,11 @@
  */
 
 pub mod create_issuer;
+pub mod create_revocation_registry;
 pub mod delete_issuer;
+pub mod update_revocation_registry;
 
 pub use self::create_issuer::*;
+pub use self::create_revocation_registry::*;
 pub use self::delete_issuer::*;
+pub use self::update_revocation_registry::*;
@@ -0,0 +1,70 @@
+/*
+ * This file is part of Albus code.
+ *
+ * Copyright (c) 2023, mFactory GmbH
+ *
+ * Albus is free software: you can redistribute it
+ * and/or modify it under the terms of the GNU Affero General Public License
+ * as published by the Free Software Foundation, either version 3
+ * of the License, or (at your option) any later version.
+ *
+ * Albus is distributed in the hope that it
+ * will be useful, but WITHOUT ANY WARRANTY; without even the implied
+ * warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.
+ * If not, see <https://www.gnu.org/licenses/agpl-3.0.html>.
+ *
+ * You can be released from the requirements of the Affero GNU General Public License
+ * by purchasing a commercial license. The purchase of such a license is
+ * mandatory as soon as you develop commercial activities using the
+ * Albus code without disclosing the source code of
+ * your own applications.
+ *
+ * The developer of this program can be contacted at <info@albus.finance>.
+ */
+
+use anchor_lang::prelude::*;
+
+use crate::errors::AlbusError;
+use crate::events::UpdateRevocationRootEvent;
+use crate::state::{Issuer, RevocationRegistry};
+
+/// Publishes a new revocation root, new proofs are pinned to it in `prove_proof_request`
+pub fn handler(
+    ctx: Context<UpdateRevocationRegistry>,
+    data: UpdateRevocationRegistryData,
+) -> Result<()> {
+    let timestamp = Clock::get()?.unix_timestamp;
+
+    let registry = &mut ctx.accounts.revocation_registry;
+    registry.root = data.root;
+    registry.updated_at = timestamp;
+
+    emit!(UpdateRevocationRootEvent {
+        issuer: registry.issuer,
+        revocation_registry: registry.key(),
+        root: registry.root,
+        timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct UpdateRevocationRegistryData {
+    pub root: [u8; 32],
+}
+
+#[derive(Accounts)]
+pub struct UpdateRevocationRegistry<'info> {
+    #[account(has_one = authority @ AlbusError::Unauthorized)]
+    pub issuer: Box<Account<'info, Issuer>>,
+
+    #[account(mut, has_one = issuer)]
+    pub revocation_registry: Box<Account<'info, RevocationRegistry>>,
+
+    pub authority: Signer<'info>,
+}
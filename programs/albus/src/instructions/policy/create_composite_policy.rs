@@ -0,0 +1,125 @@
+/*
+ * This file is part of Albus code.
+ *
+ * Copyright (c) 2023, mFactory GmbH
+ *
+ * Albus is free software: you can redistribute it
+ * and/or modify it under the terms of the GNU Affero General Public License
+ * as published by the Free Software Foundation, either version 3
+ * of the License, or (at your option) any later version.
+ *
+ * Albus is distributed in the hope that it
+ * will be useful, but WITHOUT ANY WARRANTY; without even the implied
+ * warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.
+ * If not, see <https://www.gnu.org/licenses/agpl-3.0.html>.
+ *
+ * You can be released from the requirements of the Affero GNU General Public License
+ * by purchasing a commercial license. The purchase of such a license is
+ * mandatory as soon as you develop commercial activities using the
+ * Albus code without disclosing the source code of
+ * your own applications.
+ *
+ * The developer of this program can be contacted at <info@albus.finance>.
+ */
+
+use anchor_lang::prelude::*;
+
+use crate::errors::AlbusError;
+use crate::state::{CompositePolicy, Policy, ServiceProvider, MAX_COMPOSITE_POLICY_LEN};
+use crate::utils::cmp_pubkeys;
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateCompositePolicy<'info>>,
+    data: CreateCompositePolicyData,
+) -> Result<()> {
+    let timestamp = Clock::get()?.unix_timestamp;
+
+    if data.policies.is_empty() || data.policies.len() > MAX_COMPOSITE_POLICY_LEN {
+        msg!(
+            "Error: Composite policy requires 1 to {} policies",
+            MAX_COMPOSITE_POLICY_LEN
+        );
+        return Err(AlbusError::InvalidData.into());
+    }
+
+    if data.policies.len() != ctx.remaining_accounts.len() {
+        msg!(
+            "Invalid length of provided policies, expected {}",
+            data.policies.len()
+        );
+        return Err(AlbusError::InvalidData.into());
+    }
+
+    let service = &mut ctx.accounts.service_provider;
+
+    for (idx, acc) in ctx.remaining_accounts.iter().enumerate() {
+        if !cmp_pubkeys(acc.key, &data.policies[idx]) {
+            msg!("Invalid policy account `{}`", acc.key);
+            return Err(AlbusError::InvalidData.into());
+        }
+        if data.policies[..idx].contains(acc.key) {
+            msg!("Duplicate policy `{}`", acc.key);
+            return Err(AlbusError::InvalidData.into());
+        }
+        let policy = Account::<Policy>::try_from(acc).map_err(|_e| {
+            msg!("Invalid policy account `{}`", acc.key);
+            AlbusError::InvalidData
+        })?;
+        if !cmp_pubkeys(&policy.service_provider, &service.key()) {
+            msg!("Policy `{}` belongs to another service", acc.key);
+            return Err(AlbusError::InvalidData.into());
+        }
+    }
+
+    service.composite_policy_count += 1;
+
+    let composite = &mut ctx.accounts.composite_policy;
+    composite.service_provider = service.key();
+    composite.code = data.code;
+    composite.name = data.name;
+    composite.description = data.description;
+    composite.policies = data.policies;
+    composite.created_at = timestamp;
+    composite.bump = ctx.bumps.composite_policy;
+
+    Ok(())
+}
+
+/// Data required to create a new composite policy
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct CreateCompositePolicyData {
+    pub code: String,
+    pub name: String,
+    pub description: String,
+    /// Sub-policies, the same accounts must be passed as remaining accounts
+    pub policies: Vec<Pubkey>,
+}
+
+#[derive(Accounts)]
+#[instruction(data: CreateCompositePolicyData)]
+pub struct CreateCompositePolicy<'info> {
+    #[account(mut, has_one = authority @ AlbusError::Unauthorized)]
+    pub service_provider: Box<Account<'info, ServiceProvider>>,
+
+    #[account(
+        init,
+        seeds = [
+            CompositePolicy::SEED,
+            service_provider.key().as_ref(),
+            data.code.as_bytes()
+        ],
+        bump,
+        payer = authority,
+        space = CompositePolicy::space(data.policies.len())
+    )]
+    pub composite_policy: Box<Account<'info, CompositePolicy>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
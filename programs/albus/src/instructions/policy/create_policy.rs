@@ -28,12 +28,30 @@
 
 use anchor_lang::prelude::*;
 
+use crate::errors::AlbusError;
 use crate::state::{Circuit, ServiceProvider};
 use crate::state::{Policy, PolicyRule};
 
 pub fn handler(ctx: Context<CreatePolicy>, data: CreatePolicyData) -> Result<()> {
     let timestamp = Clock::get()?.unix_timestamp;
 
+    if ctx.accounts.circuit.latest_version == 0 {
+        msg!("Error: Circuit has no published version");
+        return Err(AlbusError::CircuitNotFinalized.into());
+    }
+
+    if data.circuit_version > ctx.accounts.circuit.latest_version {
+        msg!(
+            "Error: Circuit version {} does not exist",
+            data.circuit_version
+        );
+        return Err(AlbusError::InvalidCircuitVersion.into());
+    }
+
+    let signals = ctx.accounts.circuit.signals();
+    Policy::validate_rules(&data.rules, &signals)?;
+    Policy::validate_nullifier_signal(&data.nullifier_signal, &signals)?;
+
     let service = &mut ctx.accounts.service_provider;
     service.policy_count += 1;
 
@@ -44,6 +62,8 @@ pub fn handler(ctx: Context<CreatePolicy>, data: CreatePolicyData) -> Result<()>
     policy.name = data.name;
     policy.description = data.description;
     policy.rules = data.rules;
+    policy.circuit_version = data.circuit_version;
+    policy.nullifier_signal = data.nullifier_signal;
     policy.expiration_period = data.expiration_period;
     policy.retention_period = data.retention_period;
     policy.created_at = timestamp;
@@ -61,6 +81,10 @@ pub struct CreatePolicyData {
     pub expiration_period: u32,
     pub retention_period: u32,
     pub rules: Vec<PolicyRule>,
+    /// Circuit version to pin, `0` follows the latest version
+    pub circuit_version: u32,
+    /// Signal holding the credential nullifier, empty to not enforce nullifiers
+    pub nullifier_signal: String,
 }
 
 #[derive(Accounts)]
@@ -26,10 +26,14 @@
  * The developer of this program can be contacted at <info@albus.finance>.
  */
 
+pub mod create_composite_policy;
 pub mod create_policy;
+pub mod delete_composite_policy;
 pub mod delete_policy;
 pub mod update_policy;
 
+pub use self::create_composite_policy::*;
 pub use self::create_policy::*;
+pub use self::delete_composite_policy::*;
 pub use self::delete_policy::*;
 pub use self::update_policy::*;
@@ -28,7 +28,8 @@
 
 use anchor_lang::prelude::*;
 
-use crate::state::{Policy, PolicyRule, ServiceProvider};
+use crate::errors::AlbusError;
+use crate::state::{Circuit, Policy, PolicyRule, ServiceProvider};
 
 pub fn handler(ctx: Context<UpdatePolicy>, data: UpdatePolicyData) -> Result<()> {
     let policy = &mut ctx.accounts.policy;
@@ -50,9 +51,29 @@ pub fn handler(ctx: Context<UpdatePolicy>, data: UpdatePolicyData) -> Result<()>
     }
 
     if let Some(rules) = data.rules {
+        Policy::validate_rules(&rules, &ctx.accounts.circuit.signals())?;
         policy.rules = rules;
     }
 
+    if let Some(circuit_version) = data.circuit_version {
+        if ctx.accounts.circuit.latest_version == 0 {
+            msg!("Error: Circuit has no published version");
+            return Err(AlbusError::CircuitNotFinalized.into());
+        }
+
+        if circuit_version > ctx.accounts.circuit.latest_version {
+            msg!("Error: Circuit version {} does not exist", circuit_version);
+            return Err(AlbusError::InvalidCircuitVersion.into());
+        }
+
+        policy.circuit_version = circuit_version;
+    }
+
+    if let Some(nullifier_signal) = data.nullifier_signal {
+        Policy::validate_nullifier_signal(&nullifier_signal, &ctx.accounts.circuit.signals())?;
+        policy.nullifier_signal = nullifier_signal;
+    }
+
     Ok(())
 }
 
@@ -63,13 +84,19 @@ pub struct UpdatePolicyData {
     pub expiration_period: Option<u32>,
     pub retention_period: Option<u32>,
     pub rules: Option<Vec<PolicyRule>>,
+    /// Circuit version to pin, `0` follows the latest version
+    pub circuit_version: Option<u32>,
+    /// Signal holding the credential nullifier, empty to not enforce nullifiers
+    pub nullifier_signal: Option<String>,
 }
 
 #[derive(Accounts)]
 pub struct UpdatePolicy<'info> {
-    #[account(mut, has_one = service_provider)]
+    #[account(mut, has_one = service_provider, has_one = circuit)]
     pub policy: Box<Account<'info, Policy>>,
 
+    pub circuit: Box<Account<'info, Circuit>>,
+
     #[account(has_one = authority)]
     pub service_provider: Box<Account<'info, ServiceProvider>>,
 
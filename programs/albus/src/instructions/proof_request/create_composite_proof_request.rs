@@ -0,0 +1,98 @@
+/*
+ * This file is part of Albus code.
+ *
+ * Copyright (c) 2023, mFactory GmbH
+ *
+ * Albus is free software: you can redistribute it
+ * and/or modify it under the terms of the GNU Affero General Public License
+ * as published by the Free Software Foundation, either version 3
+ * of the License, or (at your option) any later version.
+ *
+ * Albus is distributed in the hope that it
+ * will be useful, but WITHOUT ANY WARRANTY; without even the implied
+ * warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.
+ * If not, see <https://www.gnu.org/licenses/agpl-3.0.html>.
+ *
+ * You can be released from the requirements of the Affero GNU General Public License
+ * by purchasing a commercial license. The purchase of such a license is
+ * mandatory as soon as you develop commercial activities using the
+ * Albus code without disclosing the source code of
+ * your own applications.
+ *
+ * The developer of this program can be contacted at <info@albus.finance>.
+ */
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::CreateCompositeProofRequestEvent,
+    state::{CompositePolicy, CompositeProofRequest, ProofRequest, ProofRequestStatus},
+};
+
+pub fn handler(ctx: Context<CreateCompositeProofRequest>) -> Result<()> {
+    let timestamp = Clock::get()?.unix_timestamp;
+
+    let composite_policy = &mut ctx.accounts.composite_policy;
+    composite_policy.proof_request_count += 1;
+
+    let owner = ctx.accounts.authority.key();
+
+    let req = &mut ctx.accounts.composite_proof_request;
+    req.service_provider = composite_policy.service_provider;
+    req.composite_policy = composite_policy.key();
+    req.owner = owner;
+    req.created_at = timestamp;
+    req.expired_at = 0;
+    req.verified_at = 0;
+    req.status = ProofRequestStatus::Pending;
+    req.bump = ctx.bumps.composite_proof_request;
+    // component requests are created with `create_proof_request` for each sub-policy
+    req.proof_requests = composite_policy
+        .policies
+        .iter()
+        .map(|policy| {
+            Pubkey::find_program_address(
+                &[ProofRequest::SEED, policy.as_ref(), owner.as_ref()],
+                &crate::ID,
+            )
+            .0
+        })
+        .collect();
+
+    emit!(CreateCompositeProofRequestEvent {
+        service_provider: req.service_provider,
+        composite_policy: req.composite_policy,
+        owner,
+        timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateCompositeProofRequest<'info> {
+    #[account(mut)]
+    pub composite_policy: Box<Account<'info, CompositePolicy>>,
+
+    #[account(
+        init,
+        seeds = [
+            CompositeProofRequest::SEED,
+            composite_policy.key().as_ref(),
+            authority.key().as_ref(),
+        ],
+        bump,
+        payer = authority,
+        space = CompositeProofRequest::space(composite_policy.policies.len())
+    )]
+    pub composite_proof_request: Box<Account<'info, CompositeProofRequest>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -51,6 +51,8 @@ pub fn handler(ctx: Context<CreateProofRequest>, data: CreateProofRequestData) -
     req.identifier = service_provider.proof_request_count;
     req.proved_at = 0;
     req.verified_at = 0;
+    req.verified_slot = 0;
+    req.circuit_version = 0;
     req.created_at = timestamp;
     req.status = ProofRequestStatus::Pending;
     req.bump = ctx.bumps.proof_request;
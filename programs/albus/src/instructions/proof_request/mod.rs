@@ -26,14 +26,20 @@
  * The developer of this program can be contacted at <info@albus.finance>.
  */
 
+pub mod create_composite_proof_request;
 pub mod create_proof_request;
+pub mod delete_composite_proof_request;
 pub mod delete_proof_request;
 pub mod prove_proof_request;
+pub mod sync_composite_proof_request;
 pub mod update_proof_request;
 pub mod verify_proof_request;
 
+pub use self::create_composite_proof_request::*;
 pub use self::create_proof_request::*;
+pub use self::delete_composite_proof_request::*;
 pub use self::delete_proof_request::*;
 pub use self::prove_proof_request::*;
+pub use self::sync_composite_proof_request::*;
 pub use self::update_proof_request::*;
 pub use self::verify_proof_request::*;
@@ -29,10 +29,13 @@
 use anchor_lang::prelude::*;
 
 use crate::constants::{
-    ISSUER_PK_SIGNAL, META_VALID_UNTIL_SIGNAL, TIMESTAMP_SIGNAL, TIMESTAMP_THRESHOLD,
+    ISSUER_PK_SIGNAL, META_VALID_UNTIL_SIGNAL, REVOCATION_ROOT_SIGNAL, TIMESTAMP_SIGNAL,
+    TIMESTAMP_THRESHOLD,
 };
-use crate::state::{Circuit, Issuer, Policy, ProofData};
-use crate::utils::bytes_to_num;
+use crate::state::{
+    Circuit, CircuitVersion, Issuer, Nullifier, Policy, ProofData, RevocationRegistry,
+};
+use crate::utils::{bytes_to_num, initialize_account, BpfWriter};
 use crate::{
     errors::AlbusError,
     events::ProveEvent,
@@ -56,6 +59,7 @@ pub fn handler(ctx: Context<ProveProofRequest>, data: ProveProofRequestData) ->
         req.proof = None;
         req.issuer = Default::default();
         req.public_inputs.clear();
+        req.circuit_version = 0;
     }
 
     if !data.public_inputs.is_empty() {
@@ -72,6 +76,23 @@ pub fn handler(ctx: Context<ProveProofRequest>, data: ProveProofRequestData) ->
         let circuit = &ctx.accounts.circuit;
         let signals = circuit.signals();
 
+        // validate circuit version
+        let version = &ctx.accounts.circuit_version;
+        if version.version
+            != ctx
+                .accounts
+                .policy
+                .resolve_circuit_version(circuit.latest_version)
+        {
+            msg!("Error: Policy requires another circuit version");
+            return Err(AlbusError::InvalidCircuitVersion.into());
+        }
+        if version.is_deprecated() {
+            msg!("Error: Circuit version {} is deprecated", version.version);
+            return Err(AlbusError::DeprecatedCircuitVersion.into());
+        }
+        req.circuit_version = version.version;
+
         // validate timestamp
         if let Some(s) = signals.get(TIMESTAMP_SIGNAL) {
             let input = bytes_to_num(req.public_inputs[s.index]);
@@ -117,9 +138,78 @@ pub fn handler(ctx: Context<ProveProofRequest>, data: ProveProofRequestData) ->
             req.issuer = Default::default();
         }
 
+        // validate revocation root
+        if let Some(s) = signals.get(REVOCATION_ROOT_SIGNAL) {
+            match &ctx.accounts.revocation_registry {
+                Some(registry) if cmp_pubkeys(&registry.issuer, &req.issuer) => {
+                    req.public_inputs[s.index] = registry.root;
+                }
+                _ => {
+                    msg!("Error: Revocation registry of the issuer required");
+                    return Err(AlbusError::InvalidRevocationRegistry.into());
+                }
+            }
+        }
+
         // validate policy rules
         let policy = &ctx.accounts.policy;
-        policy.apply_rules(&mut req.public_inputs, &signals);
+        policy.check_rules(&req.public_inputs, &signals)?;
+
+        // register nullifier, one verified proof request per nullifier under the policy
+        if let Some(value) = policy.nullifier(&req.public_inputs, &signals)? {
+            let acc = ctx.accounts.nullifier.as_ref().ok_or_else(|| {
+                msg!("Error: Nullifier account required");
+                AlbusError::InvalidNullifier
+            })?;
+
+            let policy_key = policy.key();
+            let (addr, bump) = Pubkey::find_program_address(
+                &[Nullifier::SEED, policy_key.as_ref(), value.as_ref()],
+                &crate::ID,
+            );
+
+            if !cmp_pubkeys(acc.key, &addr) {
+                msg!("Error: Invalid nullifier account `{}`", acc.key);
+                return Err(AlbusError::InvalidNullifier.into());
+            }
+
+            let nullifier = if acc.data_is_empty() {
+                initialize_account(
+                    ctx.accounts.authority.to_account_info(),
+                    acc.to_account_info(),
+                    ctx.accounts.system_program.to_account_info(),
+                    &crate::ID,
+                    &[&[
+                        Nullifier::SEED,
+                        policy_key.as_ref(),
+                        value.as_ref(),
+                        &[bump],
+                    ]],
+                    Nullifier::space(),
+                )?;
+
+                Nullifier {
+                    policy: policy_key,
+                    proof_request: req.key(),
+                    value,
+                    created_at: timestamp,
+                    verified_at: 0,
+                    bump,
+                }
+            } else {
+                if !cmp_pubkeys(acc.owner, &crate::ID) {
+                    msg!("Error: Invalid nullifier account `{}`", acc.key);
+                    return Err(AlbusError::InvalidNullifier.into());
+                }
+                let mut nullifier = Nullifier::try_deserialize(&mut &acc.try_borrow_data()?[..])?;
+                nullifier.bind(&req.key())?;
+                nullifier
+            };
+
+            let dst: &mut [u8] = &mut acc.try_borrow_mut_data()?;
+            let mut writer: BpfWriter<&mut [u8]> = BpfWriter::new(dst);
+            nullifier.try_serialize(&mut writer)?;
+        }
 
         emit!(ProveEvent {
             proof_request: req.key(),
@@ -148,10 +238,19 @@ pub struct ProveProofRequest<'info> {
 
     pub circuit: Box<Account<'info, Circuit>>,
 
+    #[account(has_one = circuit)]
+    pub circuit_version: Box<Account<'info, CircuitVersion>>,
+
     pub policy: Box<Account<'info, Policy>>,
 
     pub issuer: Option<Box<Account<'info, Issuer>>>,
 
+    pub revocation_registry: Option<Box<Account<'info, RevocationRegistry>>>,
+
+    /// CHECK: [Nullifier] registry account, created on the first use of the nullifier
+    #[account(mut)]
+    pub nullifier: Option<UncheckedAccount<'info>>,
+
     #[account(mut)]
     pub authority: Signer<'info>,
 
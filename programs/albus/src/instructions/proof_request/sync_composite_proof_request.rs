@@ -0,0 +1,92 @@
+/*
+ * This file is part of Albus code.
+ *
+ * Copyright (c) 2023, mFactory GmbH
+ *
+ * Albus is free software: you can redistribute it
+ * and/or modify it under the terms of the GNU Affero General Public License
+ * as published by the Free Software Foundation, either version 3
+ * of the License, or (at your option) any later version.
+ *
+ * Albus is distributed in the hope that it
+ * will be useful, but WITHOUT ANY WARRANTY; without even the implied
+ * warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.
+ * If not, see <https://www.gnu.org/licenses/agpl-3.0.html>.
+ *
+ * You can be released from the requirements of the Affero GNU General Public License
+ * by purchasing a commercial license. The purchase of such a license is
+ * mandatory as soon as you develop commercial activities using the
+ * Albus code without disclosing the source code of
+ * your own applications.
+ *
+ * The developer of this program can be contacted at <info@albus.finance>.
+ */
+
+use anchor_lang::prelude::*;
+
+use crate::errors::AlbusError;
+use crate::{
+    events::SyncCompositeProofRequestEvent,
+    state::{CompositeProofRequest, ProofRequest, ProofRequestStatus},
+    utils::cmp_pubkeys,
+};
+
+/// Recomputes the aggregated status from the component proof requests,
+/// which must be passed as remaining accounts in the order of `proof_requests`
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SyncCompositeProofRequest<'info>>,
+) -> Result<()> {
+    let timestamp = Clock::get()?.unix_timestamp;
+
+    let req = &mut ctx.accounts.composite_proof_request;
+
+    if ctx.remaining_accounts.len() != req.proof_requests.len() {
+        msg!(
+            "Invalid length of provided proof requests, expected {}",
+            req.proof_requests.len()
+        );
+        return Err(AlbusError::InvalidData.into());
+    }
+
+    let mut components = Vec::with_capacity(req.proof_requests.len());
+    let mut missing = false;
+    for (idx, acc) in ctx.remaining_accounts.iter().enumerate() {
+        if !cmp_pubkeys(acc.key, &req.proof_requests[idx]) {
+            msg!("Invalid proof request account `{}`", acc.key);
+            return Err(AlbusError::InvalidData.into());
+        }
+        if acc.data_is_empty() {
+            missing = true;
+            continue;
+        }
+        components.push(Account::<ProofRequest>::try_from(acc)?);
+    }
+
+    req.aggregate(components.iter().map(|c| &**c));
+
+    // a component request that is not created yet is still pending
+    if missing && req.status != ProofRequestStatus::Rejected {
+        req.status = ProofRequestStatus::Pending;
+        req.verified_at = 0;
+    }
+
+    emit!(SyncCompositeProofRequestEvent {
+        composite_proof_request: req.key(),
+        service_provider: req.service_provider,
+        owner: req.owner,
+        status: req.status.clone(),
+        timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SyncCompositeProofRequest<'info> {
+    #[account(mut)]
+    pub composite_proof_request: Box<Account<'info, CompositeProofRequest>>,
+}
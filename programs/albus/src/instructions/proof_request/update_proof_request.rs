@@ -31,7 +31,7 @@ use anchor_lang::prelude::*;
 use crate::{
     errors::AlbusError,
     events::{RejectEvent, VerifyEvent},
-    state::{ProofRequest, ProofRequestStatus},
+    state::{Circuit, Nullifier, Policy, ProofRequest, ProofRequestStatus},
     utils::assert_authorized,
 };
 
@@ -47,7 +47,8 @@ pub fn handler(ctx: Context<UpdateProofRequest>, data: UpdateProofRequestData) -
     //     return Err(AlbusError::Unproved.into());
     // }
 
-    let timestamp = Clock::get()?.unix_timestamp;
+    let clock = Clock::get()?;
+    let timestamp = clock.unix_timestamp;
 
     // Check that the request has not yet expired
     if req.expired_at > 0 && req.expired_at < timestamp {
@@ -56,9 +57,21 @@ pub fn handler(ctx: Context<UpdateProofRequest>, data: UpdateProofRequestData) -
 
     req.status = data.status;
     req.verified_at = timestamp;
+    req.verified_slot = clock.slot;
 
     match req.status {
         ProofRequestStatus::Verified => {
+            // the nullifier must still be bound to this request
+            let policy = &ctx.accounts.policy;
+            let signals = ctx.accounts.circuit.signals();
+            if let Some(value) = policy.nullifier(&req.public_inputs, &signals)? {
+                let nullifier = ctx.accounts.nullifier.as_mut().ok_or_else(|| {
+                    msg!("Error: Nullifier account required");
+                    AlbusError::InvalidNullifier
+                })?;
+                nullifier.verify(&policy.key(), &value, &req.key(), timestamp)?;
+            }
+
             emit!(VerifyEvent {
                 proof_request: req.key(),
                 service_provider: req.service_provider,
@@ -94,9 +107,17 @@ pub struct UpdateProofRequestData {
 
 #[derive(Accounts)]
 pub struct UpdateProofRequest<'info> {
-    #[account(mut)]
+    #[account(mut, has_one = circuit, has_one = policy)]
     pub proof_request: Box<Account<'info, ProofRequest>>,
 
+    pub circuit: Box<Account<'info, Circuit>>,
+
+    pub policy: Box<Account<'info, Policy>>,
+
+    /// [Nullifier] registered when the request was proved, required if the policy enforces nullifiers
+    #[account(mut)]
+    pub nullifier: Option<Box<Account<'info, Nullifier>>>,
+
     #[account(mut)]
     pub authority: Signer<'info>,
 
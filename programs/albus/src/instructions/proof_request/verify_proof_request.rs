@@ -34,7 +34,7 @@ use groth16_solana::{Groth16Verifier, Proof, VK};
 #[cfg(feature = "verify-on-chain")]
 use crate::{events::VerifyEvent, state::ProofRequestStatus};
 
-use crate::state::Circuit;
+use crate::state::{Circuit, CircuitVersion, Nullifier, Policy};
 use crate::{errors::AlbusError, state::ProofRequest};
 
 pub fn handler(ctx: Context<VerifyProofRequest>) -> Result<()> {
@@ -42,19 +42,28 @@ pub fn handler(ctx: Context<VerifyProofRequest>) -> Result<()> {
     {
         let req = &mut ctx.accounts.proof_request;
         let circuit = &ctx.accounts.circuit;
+        let version = &ctx.accounts.circuit_version;
+
+        if version.version != req.circuit_version {
+            msg!(
+                "Error: The proof was created with circuit version {}",
+                req.circuit_version
+            );
+            return Err(AlbusError::InvalidCircuitVersion.into());
+        }
 
         let proof = req.proof.as_ref().ok_or(AlbusError::InvalidPublicInputs)?;
         let proof = Proof::new(proof.a, proof.b, proof.c);
 
         let vk = VK {
-            alpha: circuit.vk.alpha,
-            beta: circuit.vk.beta,
-            gamma: circuit.vk.gamma,
-            delta: circuit.vk.delta,
-            ic: &circuit.vk.ic,
+            alpha: version.vk.alpha,
+            beta: version.vk.beta,
+            gamma: version.vk.gamma,
+            delta: version.vk.delta,
+            ic: &version.vk.ic,
         };
 
-        Groth16Verifier::new(&proof, &req.public_inputs, &vk)
+        Groth16Verifier::new_strict(&proof, &req.public_inputs, &vk)
             .map_err(|e| {
                 msg!("Groth16Verifier: {:?}", e);
                 AlbusError::InvalidPublicInputs
@@ -65,10 +74,22 @@ pub fn handler(ctx: Context<VerifyProofRequest>) -> Result<()> {
                 AlbusError::ProofVerificationFailed
             })?;
 
-        let timestamp = Clock::get()?.unix_timestamp;
+        let clock = Clock::get()?;
+        let timestamp = clock.unix_timestamp;
+
+        // the nullifier must still be bound to this request
+        let policy = &ctx.accounts.policy;
+        if let Some(value) = policy.nullifier(&req.public_inputs, &circuit.signals())? {
+            let nullifier = ctx.accounts.nullifier.as_mut().ok_or_else(|| {
+                msg!("Error: Nullifier account required");
+                AlbusError::InvalidNullifier
+            })?;
+            nullifier.verify(&policy.key(), &value, &req.key(), timestamp)?;
+        }
 
         req.status = ProofRequestStatus::Verified;
         req.verified_at = timestamp;
+        req.verified_slot = clock.slot;
 
         emit!(VerifyEvent {
             proof_request: req.key(),
@@ -91,11 +112,20 @@ pub fn handler(ctx: Context<VerifyProofRequest>) -> Result<()> {
 
 #[derive(Accounts)]
 pub struct VerifyProofRequest<'info> {
-    #[account(mut, has_one = circuit)]
+    #[account(mut, has_one = circuit, has_one = policy)]
     pub proof_request: Box<Account<'info, ProofRequest>>,
 
     pub circuit: Box<Account<'info, Circuit>>,
 
+    #[account(has_one = circuit)]
+    pub circuit_version: Box<Account<'info, CircuitVersion>>,
+
+    pub policy: Box<Account<'info, Policy>>,
+
+    /// [Nullifier] registered when the request was proved, required if the policy enforces nullifiers
+    #[account(mut)]
+    pub nullifier: Option<Box<Account<'info, Nullifier>>>,
+
     #[account(mut)]
     pub authority: Signer<'info>,
 
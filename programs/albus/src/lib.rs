@@ -73,6 +73,20 @@ pub mod albus {
         delete_issuer::handler(ctx)
     }
 
+    pub fn create_revocation_registry(
+        ctx: Context<CreateRevocationRegistry>,
+        data: CreateRevocationRegistryData,
+    ) -> Result<()> {
+        create_revocation_registry::handler(ctx, data)
+    }
+
+    pub fn update_revocation_registry(
+        ctx: Context<UpdateRevocationRegistry>,
+        data: UpdateRevocationRegistryData,
+    ) -> Result<()> {
+        update_revocation_registry::handler(ctx, data)
+    }
+
     // Credential
 
     pub fn create_credential(ctx: Context<CreateCredential>) -> Result<()> {
@@ -134,6 +148,18 @@ pub mod albus {
         update_circuit_vk::handler(ctx, data)
     }
 
+    pub fn finalize_circuit(ctx: Context<FinalizeCircuit>) -> Result<()> {
+        finalize_circuit::handler(ctx)
+    }
+
+    pub fn create_circuit_version(ctx: Context<CreateCircuitVersion>) -> Result<()> {
+        create_circuit_version::handler(ctx)
+    }
+
+    pub fn deprecate_circuit_version(ctx: Context<DeprecateCircuitVersion>) -> Result<()> {
+        deprecate_circuit_version::handler(ctx)
+    }
+
     pub fn delete_circuit(ctx: Context<DeleteCircuit>) -> Result<()> {
         delete_circuit::handler(ctx)
     }
@@ -172,6 +198,17 @@ pub mod albus {
         delete_policy::handler(ctx)
     }
 
+    pub fn create_composite_policy<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateCompositePolicy<'info>>,
+        data: CreateCompositePolicyData,
+    ) -> Result<()> {
+        create_composite_policy::handler(ctx, data)
+    }
+
+    pub fn delete_composite_policy(ctx: Context<DeleteCompositePolicy>) -> Result<()> {
+        delete_composite_policy::handler(ctx)
+    }
+
     // Trustee
 
     pub fn create_trustee(ctx: Context<CreateTrustee>, data: CreateTrusteeData) -> Result<()> {
@@ -221,6 +258,20 @@ pub mod albus {
         update_proof_request::handler(ctx, data)
     }
 
+    pub fn create_composite_proof_request(ctx: Context<CreateCompositeProofRequest>) -> Result<()> {
+        create_composite_proof_request::handler(ctx)
+    }
+
+    pub fn sync_composite_proof_request<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SyncCompositeProofRequest<'info>>,
+    ) -> Result<()> {
+        sync_composite_proof_request::handler(ctx)
+    }
+
+    pub fn delete_composite_proof_request(ctx: Context<DeleteCompositeProofRequest>) -> Result<()> {
+        delete_composite_proof_request::handler(ctx)
+    }
+
     // Investigation
 
     pub fn create_investigation_request<'info>(
@@ -252,4 +303,8 @@ pub mod albus {
     pub fn admin_withdraw(ctx: Context<AdminWithdraw>) -> Result<()> {
         withdraw::handler(ctx)
     }
+
+    pub fn admin_migrate_account(ctx: Context<AdminMigrateAccount>) -> Result<()> {
+        migrate_account::handler(ctx)
+    }
 }
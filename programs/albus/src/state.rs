@@ -26,8 +26,10 @@
  * The developer of this program can be contacted at <info@albus.finance>.
  */
 
+use crate::errors::AlbusError;
 use crate::utils::Signals;
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
 use std::collections::HashMap;
 
 #[cfg(feature = "verify-on-chain")]
@@ -65,6 +67,13 @@ impl VerificationKey {
     pub fn space(public_inputs_len: usize) -> usize {
         Self::INIT_SPACE + ((public_inputs_len + 1) * 64)
     }
+
+    /// SHA-256 of `alpha || beta || gamma || delta || ic[0] || .. || ic[n]`
+    pub fn hash(&self) -> [u8; 32] {
+        let mut data: Vec<&[u8]> = vec![&self.alpha, &self.beta, &self.gamma, &self.delta];
+        data.extend(self.ic.iter().map(|p| p.as_slice()));
+        hashv(&data).to_bytes()
+    }
 }
 
 pub const MAX_ISSUER_CODE_LEN: usize = 32;
@@ -118,6 +127,31 @@ impl Issuer {
     }
 }
 
+/// Issuer-managed registry of revoked credentials
+#[account]
+#[derive(InitSpace)]
+pub struct RevocationRegistry {
+    /// The [Issuer] that manages the registry
+    pub issuer: Pubkey,
+    /// Root of the sparse Merkle tree of revoked credentials (scalar field, big-endian)
+    pub root: [u8; 32],
+    /// Creation date
+    pub created_at: i64,
+    /// Timestamp for when the `root` was last updated
+    pub updated_at: i64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl RevocationRegistry {
+    pub const SEED: &'static [u8] = b"revocation-registry";
+
+    #[inline]
+    pub fn space() -> usize {
+        8 + Self::INIT_SPACE
+    }
+}
+
 pub const MAX_CIRCUIT_CODE_LEN: usize = 16;
 pub const MAX_CIRCUIT_NAME_LEN: usize = 32;
 pub const MAX_CIRCUIT_DESC_LEN: usize = 64;
@@ -154,6 +188,16 @@ pub struct Circuit {
     /// Private signals associated with the circuit
     #[max_len(0, 0)]
     pub private_signals: Vec<String>,
+    /// SHA-256 of the `.wasm` file at `wasm_uri`
+    pub wasm_hash: [u8; 32],
+    /// SHA-256 of the `.zkey` file at `zkey_uri`
+    pub zkey_hash: [u8; 32],
+    /// Hash of the verification key, see [VerificationKey::hash]
+    pub vk_hash: [u8; 32],
+    /// Latest published [CircuitVersion], `0` if none
+    pub latest_version: u32,
+    /// Status of the staged verification key
+    pub status: CircuitStatus,
 }
 
 impl Circuit {
@@ -172,6 +216,17 @@ impl Circuit {
         Signals::new(signals).len()
     }
 
+    /// Number of public inputs (outputs and public signals) of the circuit
+    #[inline]
+    pub fn public_inputs_count(&self) -> usize {
+        Self::signals_count(&self.outputs) + Self::signals_count(&self.public_signals)
+    }
+
+    #[inline]
+    pub fn is_finalized(&self) -> bool {
+        self.status == CircuitStatus::Finalized
+    }
+
     #[inline]
     pub fn signals(&self) -> Signals {
         let mut vec = Vec::with_capacity(self.outputs.len() + self.public_signals.len());
@@ -181,6 +236,66 @@ impl Circuit {
     }
 }
 
+#[repr(u8)]
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Eq, PartialEq, Clone, InitSpace)]
+pub enum CircuitStatus {
+    /// The verification key is being uploaded
+    #[default]
+    Draft,
+    /// The verification key is validated and can be published
+    Finalized,
+}
+
+/// Immutable snapshot of the circuit verification key.
+/// The `vk` of the [Circuit] is only a staging area for the next version.
+#[account]
+#[derive(InitSpace)]
+pub struct CircuitVersion {
+    /// The [Circuit] this version belongs to
+    pub circuit: Pubkey,
+    /// Sequential version number, starting from 1
+    pub version: u32,
+    /// Version status
+    pub status: CircuitVersionStatus,
+    /// SHA-256 of the `.wasm` file of this version
+    pub wasm_hash: [u8; 32],
+    /// SHA-256 of the `.zkey` file of this version
+    pub zkey_hash: [u8; 32],
+    /// Hash of the verification key, see [VerificationKey::hash]
+    pub vk_hash: [u8; 32],
+    /// Creation date
+    pub created_at: i64,
+    /// Deprecation date
+    pub deprecated_at: i64,
+    /// PDA bump
+    pub bump: u8,
+    /// Verification key
+    pub vk: VerificationKey,
+}
+
+impl CircuitVersion {
+    pub const SEED: &'static [u8] = b"circuit-version";
+
+    /// `INIT_SPACE` already includes the fixed part of the [VerificationKey]
+    #[inline]
+    pub fn space(public_len: usize) -> usize {
+        8 + Self::INIT_SPACE + (public_len + 1) * 64
+    }
+
+    #[inline]
+    pub fn is_deprecated(&self) -> bool {
+        self.status == CircuitVersionStatus::Deprecated
+    }
+}
+
+#[repr(u8)]
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Eq, PartialEq, Clone, InitSpace)]
+pub enum CircuitVersionStatus {
+    #[default]
+    Active,
+    Deprecated,
+}
+
 pub const MAX_POLICY_CODE_LEN: usize = 16;
 pub const MAX_POLICY_NAME_LEN: usize = 32;
 pub const MAX_POLICY_DESC_LEN: usize = 64;
@@ -214,6 +329,11 @@ pub struct Policy {
     /// Policy rules
     #[max_len(0)]
     pub rules: Vec<PolicyRule>,
+    /// Pinned [CircuitVersion], `0` follows the latest version of the circuit
+    pub circuit_version: u32,
+    /// Signal holding the credential nullifier, empty if nullifiers are not enforced
+    #[max_len(MAX_POLICY_RULE_KEY_LEN)]
+    pub nullifier_signal: String,
 }
 
 impl Policy {
@@ -224,19 +344,159 @@ impl Policy {
         8 + Self::INIT_SPACE + (rules_len * PolicyRule::INIT_SPACE)
     }
 
+    /// Returns the circuit version new proofs must be created with
     #[inline]
-    pub fn apply_rules(&self, public_inputs: &mut [[u8; 32]], signals: &Signals) {
+    pub fn resolve_circuit_version(&self, latest_version: u32) -> u32 {
+        if self.circuit_version > 0 {
+            self.circuit_version
+        } else {
+            latest_version
+        }
+    }
+
+    /// Validates that every rule targets an existing signal,
+    /// that an array signal has no more positional rules than its size
+    /// and that `MerkleRoot` rules hold a non-zero root of a scalar signal.
+    pub fn validate_rules(rules: &[PolicyRule], signals: &Signals) -> Result<()> {
+        let mut counts = HashMap::with_capacity(rules.len());
+        for rule in rules {
+            let signal = signals.get(&rule.key).ok_or_else(|| {
+                msg!("Error: Policy rule signal `{}` not found", rule.key);
+                AlbusError::PolicyRuleSignalNotFound
+            })?;
+
+            if rule.operator == PolicyRuleOperator::MerkleRoot
+                && (signal.size != 1 || rule.value == [0; 32])
+            {
+                msg!("Error: Invalid Merkle root rule for `{}`", rule.key);
+                return Err(AlbusError::PolicyRuleInvalidMerkleRoot.into());
+            }
+
+            if matches!(
+                rule.operator,
+                PolicyRuleOperator::InSet | PolicyRuleOperator::MerkleRoot
+            ) {
+                continue;
+            }
+
+            let count = counts.entry(&rule.key).or_insert(0);
+            *count += 1;
+            if *count > signal.size {
+                msg!(
+                    "Error: Too many policy rules for `{}`, expected at most {}",
+                    rule.key,
+                    signal.size
+                );
+                return Err(AlbusError::PolicyRuleTooManyValues.into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Validates that the nullifier signal, if set, is a scalar signal of the circuit
+    pub fn validate_nullifier_signal(name: &str, signals: &Signals) -> Result<()> {
+        if name.is_empty() {
+            return Ok(());
+        }
+        match signals.get(name) {
+            Some(signal) if signal.size == 1 => Ok(()),
+            _ => {
+                msg!("Error: Nullifier signal `{}` not found", name);
+                Err(AlbusError::InvalidNullifier.into())
+            }
+        }
+    }
+
+    /// Returns the nullifier value from the public inputs,
+    /// or `None` if the policy does not enforce nullifiers
+    pub fn nullifier(
+        &self,
+        public_inputs: &[[u8; 32]],
+        signals: &Signals,
+    ) -> Result<Option<[u8; 32]>> {
+        if self.nullifier_signal.is_empty() {
+            return Ok(None);
+        }
+        let signal = signals.get(&self.nullifier_signal).ok_or_else(|| {
+            msg!(
+                "Error: Nullifier signal `{}` not found",
+                self.nullifier_signal
+            );
+            AlbusError::InvalidNullifier
+        })?;
+        let value = public_inputs
+            .get(signal.index)
+            .ok_or(AlbusError::InvalidPublicInputs)?;
+        Ok(Some(*value))
+    }
+
+    /// Evaluates the rules against the public inputs.
+    /// Successive rules of the same key apply to successive elements of an array signal,
+    /// except `InSet` rules which together form the set of allowed values of every element,
+    /// and `MerkleRoot` rules which together form the set of accepted roots.
+    pub fn check_rules(&self, public_inputs: &[[u8; 32]], signals: &Signals) -> Result<()> {
         let mut used_indices = HashMap::with_capacity(self.rules.len());
         for rule in &self.rules {
-            if let Some(signal) = signals.get(&rule.key) {
-                let idx = used_indices.entry(&rule.key).or_insert(0);
-                let index = signal.index + *idx;
-                if let Some(slot) = public_inputs.get_mut(index) {
-                    *slot = rule.value;
-                    *idx += 1;
+            let signal = signals.get(&rule.key).ok_or_else(|| {
+                msg!("Error: Policy rule signal `{}` not found", rule.key);
+                AlbusError::PolicyRuleSignalNotFound
+            })?;
+
+            if rule.operator == PolicyRuleOperator::InSet {
+                let inputs = public_inputs
+                    .get(signal.index..signal.index + signal.size)
+                    .ok_or(AlbusError::InvalidPublicInputs)?;
+                for input in inputs {
+                    if !self.rules.iter().any(|r| {
+                        r.operator == PolicyRuleOperator::InSet
+                            && r.key == rule.key
+                            && r.value == *input
+                    }) {
+                        msg!("Error: `{}` is not in the allowed set", rule.key);
+                        return Err(AlbusError::PolicyRuleNotInSet.into());
+                    }
+                }
+                continue;
+            }
+
+            if rule.operator == PolicyRuleOperator::MerkleRoot {
+                let root = public_inputs
+                    .get(signal.index)
+                    .ok_or(AlbusError::InvalidPublicInputs)?;
+                if *root == [0; 32]
+                    || !self.rules.iter().any(|r| {
+                        r.operator == PolicyRuleOperator::MerkleRoot
+                            && r.key == rule.key
+                            && r.value == *root
+                    })
+                {
+                    msg!("Error: `{}` is not an accepted Merkle root", rule.key);
+                    return Err(AlbusError::PolicyRuleInvalidMerkleRoot.into());
                 }
+                continue;
+            }
+
+            let idx = used_indices.entry(&rule.key).or_insert(0);
+            if *idx >= signal.size {
+                msg!("Error: Too many policy rules for `{}`", rule.key);
+                return Err(AlbusError::PolicyRuleTooManyValues.into());
             }
+            let input = public_inputs
+                .get(signal.index + *idx)
+                .ok_or(AlbusError::InvalidPublicInputs)?;
+            *idx += 1;
+
+            // scalars are big-endian, so byte order is numeric order
+            let err = match rule.operator {
+                PolicyRuleOperator::Eq if *input != rule.value => AlbusError::PolicyRuleNotEqual,
+                PolicyRuleOperator::Gte if *input < rule.value => AlbusError::PolicyRuleTooLow,
+                PolicyRuleOperator::Lte if *input > rule.value => AlbusError::PolicyRuleTooHigh,
+                _ => continue,
+            };
+            msg!("Error: Policy rule `{}` is not satisfied", rule.key);
+            return Err(err.into());
         }
+        Ok(())
     }
 }
 
@@ -251,6 +511,63 @@ pub struct PolicyRule {
     pub value: [u8; 32],
     #[max_len(MAX_POLICY_RULE_LABEL_LEN)]
     pub label: String,
+    /// How the public input is compared to the `value`
+    pub operator: PolicyRuleOperator,
+}
+
+#[repr(u8)]
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Eq, PartialEq, Clone, Copy, InitSpace)]
+pub enum PolicyRuleOperator {
+    /// The input equals the `value`
+    #[default]
+    Eq,
+    /// The input is greater than or equal to the `value`
+    Gte,
+    /// The input is less than or equal to the `value`
+    Lte,
+    /// The input equals the `value` of one of the `InSet` rules with the same key
+    InSet,
+    /// The input is the root of the Merkle tree the circuit proved membership in,
+    /// and equals the `value` of one of the `MerkleRoot` rules with the same key,
+    /// so the allowed set can be updated while proofs against the previous root stay valid
+    MerkleRoot,
+}
+
+pub const MAX_COMPOSITE_POLICY_LEN: usize = 8;
+
+/// AND-composition of [Policy] accounts, possibly bound to different circuits
+#[account]
+#[derive(InitSpace)]
+pub struct CompositePolicy {
+    /// The service provider this belongs to
+    pub service_provider: Pubkey,
+    /// Unique code of the composite policy (associated with the service)
+    #[max_len(MAX_POLICY_CODE_LEN)]
+    pub code: String,
+    /// Name of the composite policy
+    #[max_len(MAX_POLICY_NAME_LEN)]
+    pub name: String,
+    /// Short description
+    #[max_len(MAX_POLICY_DESC_LEN)]
+    pub description: String,
+    /// Total number of composite proof requests
+    pub proof_request_count: u64,
+    /// Creation date
+    pub created_at: i64,
+    /// PDA bump
+    pub bump: u8,
+    /// Sub-policies that must all be satisfied
+    #[max_len(0)]
+    pub policies: Vec<Pubkey>,
+}
+
+impl CompositePolicy {
+    pub const SEED: &'static [u8] = b"composite-policy";
+
+    #[inline]
+    pub fn space(policies_len: usize) -> usize {
+        8 + Self::INIT_SPACE + (policies_len * 32)
+    }
 }
 
 #[account]
@@ -282,6 +599,8 @@ pub struct ServiceProvider {
     /// List of selected trustees
     #[max_len(3)]
     pub trustees: Vec<Pubkey>,
+    /// Total number of composite policies
+    pub composite_policy_count: u64,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, InitSpace)]
@@ -461,6 +780,10 @@ pub struct ProofRequest {
     /// Public inputs that are used to verify the `proof`
     #[max_len(0)]
     pub public_inputs: Vec<[u8; 32]>,
+    /// The [CircuitVersion] the `proof` was created with
+    pub circuit_version: u32,
+    /// Slot in which the `proof` was verified
+    pub verified_slot: u64,
 }
 
 impl ProofRequest {
@@ -490,6 +813,142 @@ pub enum ProofRequestStatus {
     Rejected,
 }
 
+/// Registry entry of a nullifier used under a [Policy]
+#[account]
+#[derive(InitSpace)]
+pub struct Nullifier {
+    /// The [Policy] the nullifier is scoped to
+    pub policy: Pubkey,
+    /// The [ProofRequest] that used the nullifier
+    pub proof_request: Pubkey,
+    /// Nullifier value
+    pub value: [u8; 32],
+    /// Creation date
+    pub created_at: i64,
+    /// Verification date of the `proof_request`, the nullifier is bound to it from then on
+    pub verified_at: i64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl Nullifier {
+    pub const SEED: &'static [u8] = b"nullifier";
+
+    #[inline]
+    pub fn space() -> usize {
+        8 + Self::INIT_SPACE
+    }
+
+    /// Binds the nullifier to the `proof_request`.
+    /// Until the bound request is verified, another request may take the nullifier over,
+    /// so an unverifiable proof cannot squat a nullifier it does not own.
+    pub fn bind(&mut self, proof_request: &Pubkey) -> Result<()> {
+        if self.proof_request != *proof_request {
+            if self.verified_at > 0 {
+                msg!("Error: Nullifier has already been used");
+                return Err(AlbusError::NullifierAlreadyUsed.into());
+            }
+            self.proof_request = *proof_request;
+        }
+        Ok(())
+    }
+
+    /// Marks the nullifier as used by the verified `proof_request`,
+    /// which must be the request the nullifier is currently bound to
+    pub fn verify(
+        &mut self,
+        policy: &Pubkey,
+        value: &[u8; 32],
+        proof_request: &Pubkey,
+        timestamp: i64,
+    ) -> Result<()> {
+        if self.policy != *policy || self.value != *value {
+            msg!("Error: Invalid nullifier account");
+            return Err(AlbusError::InvalidNullifier.into());
+        }
+        if self.proof_request != *proof_request {
+            msg!("Error: Nullifier is bound to another proof request");
+            return Err(AlbusError::NullifierAlreadyUsed.into());
+        }
+        self.verified_at = timestamp;
+        Ok(())
+    }
+}
+
+/// Aggregated request of a [CompositePolicy], one component [ProofRequest] per sub-policy
+#[account]
+#[derive(InitSpace)]
+pub struct CompositeProofRequest {
+    /// The [ServiceProvider] associated with this request
+    pub service_provider: Pubkey,
+    /// The [CompositePolicy] associated with this request
+    pub composite_policy: Pubkey,
+    /// Proof request creator
+    pub owner: Pubkey,
+    /// Timestamp for when the request was created
+    pub created_at: i64,
+    /// Earliest expiration of the component requests, `0` if none expires
+    pub expired_at: i64,
+    /// Timestamp for when the last component request was verified
+    pub verified_at: i64,
+    /// Aggregated status of the component requests
+    pub status: ProofRequestStatus,
+    /// PDA bump
+    pub bump: u8,
+    /// Component proof requests, in the order of [CompositePolicy::policies]
+    #[max_len(0)]
+    pub proof_requests: Vec<Pubkey>,
+}
+
+impl CompositeProofRequest {
+    pub const SEED: &'static [u8] = b"composite-proof-request";
+
+    #[inline]
+    pub fn space(proof_requests_len: usize) -> usize {
+        8 + Self::INIT_SPACE + (proof_requests_len * 32)
+    }
+
+    pub fn is_verified(&self) -> bool {
+        self.status == ProofRequestStatus::Verified
+    }
+
+    /// Aggregates the state of the component requests.
+    /// The status is `Verified` only when every component is verified,
+    /// `Rejected` when any component is rejected, `Proved` when every component is proved.
+    pub fn aggregate<'a>(&mut self, components: impl IntoIterator<Item = &'a ProofRequest>) {
+        let mut status = ProofRequestStatus::Verified;
+        let mut expired_at = 0;
+        let mut verified_at = 0;
+
+        for req in components {
+            status = match (&status, &req.status) {
+                (ProofRequestStatus::Rejected, _) | (_, ProofRequestStatus::Rejected) => {
+                    ProofRequestStatus::Rejected
+                }
+                (ProofRequestStatus::Pending, _) | (_, ProofRequestStatus::Pending) => {
+                    ProofRequestStatus::Pending
+                }
+                (ProofRequestStatus::Proved, _) | (_, ProofRequestStatus::Proved) => {
+                    ProofRequestStatus::Proved
+                }
+                _ => ProofRequestStatus::Verified,
+            };
+            if req.expired_at > 0 && (expired_at == 0 || req.expired_at < expired_at) {
+                expired_at = req.expired_at;
+            }
+            verified_at = verified_at.max(req.verified_at);
+        }
+
+        if status != ProofRequestStatus::Verified {
+            verified_at = 0;
+        }
+
+        self.status = status;
+        self.expired_at = expired_at;
+        self.verified_at = verified_at;
+    }
+}
+
 // On-chain credential
 //
 // pub const MAX_CREDENTIAL_URI_LEN: usize = 200;
@@ -619,14 +1078,289 @@ impl CredentialSpec {
     }
 }
 
+// Layouts of the accounts created before fields were appended to them,
+// converted to the current layouts by `admin_migrate_account`
+
+/// [Circuit] before the artifact hashes, `latest_version` and `status` were added
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct LegacyCircuit {
+    #[max_len(MAX_CIRCUIT_CODE_LEN)]
+    pub code: String,
+    #[max_len(MAX_CIRCUIT_NAME_LEN)]
+    pub name: String,
+    #[max_len(MAX_CIRCUIT_DESC_LEN)]
+    pub description: String,
+    #[max_len(MAX_CIRCUIT_URI_LEN)]
+    pub wasm_uri: String,
+    #[max_len(MAX_CIRCUIT_URI_LEN)]
+    pub zkey_uri: String,
+    pub created_at: i64,
+    pub bump: u8,
+    pub vk: VerificationKey,
+    #[max_len(0, 0)]
+    pub outputs: Vec<String>,
+    #[max_len(0, 0)]
+    pub public_signals: Vec<String>,
+    #[max_len(0, 0)]
+    pub private_signals: Vec<String>,
+}
+
+impl LegacyCircuit {
+    #[inline]
+    pub fn space(public_len: usize, private_len: usize) -> usize {
+        8 + Self::INIT_SPACE
+            + VerificationKey::space(public_len)
+            + (public_len + private_len) * Circuit::MAX_SIGNAL_NAME_LEN
+    }
+}
+
+/// The migrated circuit is a `Draft` without versions,
+/// it must be finalized and published before new policies can use it.
+impl From<LegacyCircuit> for Circuit {
+    fn from(value: LegacyCircuit) -> Self {
+        Self {
+            code: value.code,
+            name: value.name,
+            description: value.description,
+            wasm_uri: value.wasm_uri,
+            zkey_uri: value.zkey_uri,
+            created_at: value.created_at,
+            bump: value.bump,
+            vk: value.vk,
+            outputs: value.outputs,
+            public_signals: value.public_signals,
+            private_signals: value.private_signals,
+            wasm_hash: Default::default(),
+            zkey_hash: Default::default(),
+            vk_hash: Default::default(),
+            latest_version: 0,
+            status: CircuitStatus::Draft,
+        }
+    }
+}
+
+/// [PolicyRule] before the `operator` was added
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct LegacyPolicyRule {
+    #[max_len(MAX_POLICY_RULE_KEY_LEN)]
+    pub key: String,
+    pub value: [u8; 32],
+    #[max_len(MAX_POLICY_RULE_LABEL_LEN)]
+    pub label: String,
+}
+
+/// Legacy rules required the input to be equal to the `value`
+impl From<LegacyPolicyRule> for PolicyRule {
+    fn from(value: LegacyPolicyRule) -> Self {
+        Self {
+            key: value.key,
+            value: value.value,
+            label: value.label,
+            operator: PolicyRuleOperator::Eq,
+        }
+    }
+}
+
+/// [Policy] before the rule operators, `circuit_version` and `nullifier_signal` were added
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct LegacyPolicy {
+    pub service_provider: Pubkey,
+    pub circuit: Pubkey,
+    #[max_len(MAX_POLICY_CODE_LEN)]
+    pub code: String,
+    #[max_len(MAX_POLICY_NAME_LEN)]
+    pub name: String,
+    #[max_len(MAX_POLICY_DESC_LEN)]
+    pub description: String,
+    pub expiration_period: u32,
+    pub retention_period: u32,
+    pub proof_request_count: u64,
+    pub created_at: i64,
+    pub bump: u8,
+    #[max_len(0)]
+    pub rules: Vec<LegacyPolicyRule>,
+}
+
+impl LegacyPolicy {
+    #[inline]
+    pub fn space(rules_len: usize) -> usize {
+        8 + Self::INIT_SPACE + (rules_len * LegacyPolicyRule::INIT_SPACE)
+    }
+}
+
+/// The migrated policy follows the latest circuit version and does not enforce nullifiers
+impl From<LegacyPolicy> for Policy {
+    fn from(value: LegacyPolicy) -> Self {
+        Self {
+            service_provider: value.service_provider,
+            circuit: value.circuit,
+            code: value.code,
+            name: value.name,
+            description: value.description,
+            expiration_period: value.expiration_period,
+            retention_period: value.retention_period,
+            proof_request_count: value.proof_request_count,
+            created_at: value.created_at,
+            bump: value.bump,
+            rules: value.rules.into_iter().map(Into::into).collect(),
+            circuit_version: 0,
+            nullifier_signal: String::new(),
+        }
+    }
+}
+
+/// [ServiceProvider] before the `composite_policy_count` was added
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct LegacyServiceProvider {
+    pub authority: Pubkey,
+    #[max_len(16)]
+    pub code: String,
+    #[max_len(32)]
+    pub name: String,
+    #[max_len(200)]
+    pub website: String,
+    pub contact_info: ContactInfo,
+    pub proof_request_count: u64,
+    pub policy_count: u64,
+    pub created_at: i64,
+    pub bump: u8,
+    pub secret_share_threshold: u8,
+    #[max_len(3)]
+    pub trustees: Vec<Pubkey>,
+}
+
+impl LegacyServiceProvider {
+    #[inline]
+    pub fn space() -> usize {
+        8 + Self::INIT_SPACE
+    }
+}
+
+/// Composite policies were counted in `policy_count`, the new counter starts from zero
+impl From<LegacyServiceProvider> for ServiceProvider {
+    fn from(value: LegacyServiceProvider) -> Self {
+        Self {
+            authority: value.authority,
+            code: value.code,
+            name: value.name,
+            website: value.website,
+            contact_info: value.contact_info,
+            proof_request_count: value.proof_request_count,
+            policy_count: value.policy_count,
+            created_at: value.created_at,
+            bump: value.bump,
+            secret_share_threshold: value.secret_share_threshold,
+            trustees: value.trustees,
+            composite_policy_count: 0,
+        }
+    }
+}
+
+/// [ProofRequest] before the `circuit_version` and `verified_slot` were added
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct LegacyProofRequest {
+    pub service_provider: Pubkey,
+    pub policy: Pubkey,
+    pub circuit: Pubkey,
+    pub issuer: Pubkey,
+    pub owner: Pubkey,
+    pub identifier: u64,
+    pub created_at: i64,
+    pub expired_at: i64,
+    pub verified_at: i64,
+    pub proved_at: i64,
+    pub retention_end_date: i64,
+    pub status: ProofRequestStatus,
+    pub bump: u8,
+    pub proof: Option<ProofData>,
+    #[max_len(0)]
+    pub public_inputs: Vec<[u8; 32]>,
+}
+
+impl LegacyProofRequest {
+    #[inline]
+    pub fn space(max_public_inputs: usize) -> usize {
+        8 + Self::INIT_SPACE + (max_public_inputs * 32)
+    }
+}
+
+/// The migrated request has no circuit version and verification slot,
+/// a proved request must be proved again
+impl From<LegacyProofRequest> for ProofRequest {
+    fn from(value: LegacyProofRequest) -> Self {
+        Self {
+            service_provider: value.service_provider,
+            policy: value.policy,
+            circuit: value.circuit,
+            issuer: value.issuer,
+            owner: value.owner,
+            identifier: value.identifier,
+            created_at: value.created_at,
+            expired_at: value.expired_at,
+            verified_at: value.verified_at,
+            proved_at: value.proved_at,
+            retention_end_date: value.retention_end_date,
+            status: value.status,
+            bump: value.bump,
+            proof: value.proof,
+            public_inputs: value.public_inputs,
+            circuit_version: 0,
+            verified_slot: 0,
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::utils::num_to_bytes;
 
     #[test]
-    fn test_apply_rules() {
-        let policy = Policy {
+    fn test_vk_hash() {
+        let mut vk = VerificationKey {
+            alpha: [1; 64],
+            beta: [2; 128],
+            gamma: [3; 128],
+            delta: [4; 128],
+            ic: vec![[5; 64]],
+        };
+        let hash = vk.hash();
+        assert_eq!(
+            hash,
+            hashv(&[&[1; 64], &[2; 128], &[3; 128], &[4; 128], &[5; 64]]).to_bytes()
+        );
+
+        vk.ic.push([6; 64]);
+        assert_ne!(vk.hash(), hash);
+    }
+
+    #[test]
+    fn test_circuit_version_space() {
+        let version = CircuitVersion {
+            circuit: Default::default(),
+            version: 1,
+            status: CircuitVersionStatus::Active,
+            wasm_hash: [0; 32],
+            zkey_hash: [0; 32],
+            vk_hash: [0; 32],
+            created_at: 0,
+            deprecated_at: 0,
+            bump: 0,
+            vk: VerificationKey {
+                alpha: [0; 64],
+                beta: [0; 128],
+                gamma: [0; 128],
+                delta: [0; 128],
+                ic: vec![[0; 64]; 3],
+            },
+        };
+        let mut data = vec![];
+        version.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), CircuitVersion::space(2));
+    }
+
+    fn policy(rules: Vec<PolicyRule>) -> Policy {
+        Policy {
             service_provider: Default::default(),
             circuit: Default::default(),
             code: "".to_string(),
@@ -637,37 +1371,299 @@ mod test {
             proof_request_count: 0,
             created_at: 0,
             bump: 0,
-            rules: vec![
-                PolicyRule {
-                    key: "minAge".to_string(),
-                    value: num_to_bytes(18),
-                    label: "".to_string(),
-                },
-                PolicyRule {
-                    key: "maxAge".to_string(),
-                    value: num_to_bytes(100),
-                    label: "".to_string(),
-                },
-                PolicyRule {
-                    key: "countryLookup".to_string(),
-                    value: [0; 32],
-                    label: "".to_string(),
-                },
-                PolicyRule {
-                    key: "countryLookup".to_string(),
-                    value: [0; 32],
-                    label: "".to_string(),
-                },
-            ],
+            rules,
+            circuit_version: 0,
+            nullifier_signal: "".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_resolve_circuit_version() {
+        let mut policy = policy(vec![]);
+        assert_eq!(policy.resolve_circuit_version(3), 3);
+
+        policy.circuit_version = 2;
+        assert_eq!(policy.resolve_circuit_version(3), 2);
+    }
+
+    #[test]
+    fn test_validate_rules() {
+        let signals = Signals::new(&["age", "countryLookup[2]"]);
+
+        let rules = vec![
+            rule("age", PolicyRuleOperator::Gte, 18),
+            rule("countryLookup", PolicyRuleOperator::Eq, 7),
+            rule("countryLookup", PolicyRuleOperator::Eq, 8),
+        ];
+        assert!(Policy::validate_rules(&rules, &signals).is_ok());
+
+        let mut too_many = rules.clone();
+        too_many.push(rule("countryLookup", PolicyRuleOperator::Eq, 9));
+        assert_eq!(
+            Policy::validate_rules(&too_many, &signals),
+            Err(AlbusError::PolicyRuleTooManyValues.into())
+        );
+
+        let mut set = rules.clone();
+        set.extend((0..5).map(|v| rule("age", PolicyRuleOperator::InSet, v)));
+        assert!(Policy::validate_rules(&set, &signals).is_ok());
+
+        let mut roots = rules.clone();
+        roots.push(rule("age", PolicyRuleOperator::MerkleRoot, 99));
+        roots.push(rule("age", PolicyRuleOperator::MerkleRoot, 100));
+        assert!(Policy::validate_rules(&roots, &signals).is_ok());
+
+        for invalid in [
+            rule("age", PolicyRuleOperator::MerkleRoot, 0),
+            rule("countryLookup", PolicyRuleOperator::MerkleRoot, 99),
+        ] {
+            assert_eq!(
+                Policy::validate_rules(&[invalid], &signals),
+                Err(AlbusError::PolicyRuleInvalidMerkleRoot.into())
+            );
+        }
+
+        let mut unknown = rules;
+        unknown.push(rule("minAge", PolicyRuleOperator::Eq, 18));
+        assert_eq!(
+            Policy::validate_rules(&unknown, &signals),
+            Err(AlbusError::PolicyRuleSignalNotFound.into())
+        );
+    }
+
+    #[test]
+    fn test_validate_nullifier_signal() {
+        let signals = Signals::new(&["nullifier", "countryLookup[2]"]);
+        assert!(Policy::validate_nullifier_signal("", &signals).is_ok());
+        assert!(Policy::validate_nullifier_signal("nullifier", &signals).is_ok());
+        for name in ["countryLookup", "unknown"] {
+            assert_eq!(
+                Policy::validate_nullifier_signal(name, &signals),
+                Err(AlbusError::InvalidNullifier.into())
+            );
+        }
+    }
+
+    #[test]
+    fn test_nullifier_squatting() {
+        let policy = Pubkey::new_unique();
+        let value = [7; 32];
+        let holder = Pubkey::new_unique();
+        let squatter = Pubkey::new_unique();
+
+        // the squatter registers the nullifier first, with a proof that will never verify
+        let mut nullifier = Nullifier {
+            policy,
+            proof_request: squatter,
+            value,
+            created_at: 1,
+            verified_at: 0,
+            bump: 0,
+        };
+
+        // the holder takes it over while the squatter's request is unverified
+        assert!(nullifier.bind(&holder).is_ok());
+        assert_eq!(nullifier.proof_request, holder);
+
+        // only the bound request can be verified
+        assert_eq!(
+            nullifier.verify(&policy, &value, &squatter, 2),
+            Err(AlbusError::NullifierAlreadyUsed.into())
+        );
+        assert_eq!(
+            nullifier.verify(&Pubkey::new_unique(), &value, &holder, 2),
+            Err(AlbusError::InvalidNullifier.into())
+        );
+        assert_eq!(
+            nullifier.verify(&policy, &[8; 32], &holder, 2),
+            Err(AlbusError::InvalidNullifier.into())
+        );
+        assert!(nullifier.verify(&policy, &value, &holder, 2).is_ok());
+        assert_eq!(nullifier.verified_at, 2);
+
+        // once verified, the nullifier can no longer be taken over
+        assert!(nullifier.bind(&holder).is_ok());
+        assert_eq!(
+            nullifier.bind(&squatter),
+            Err(AlbusError::NullifierAlreadyUsed.into())
+        );
+        assert_eq!(nullifier.proof_request, holder);
+    }
+
+    #[test]
+    fn test_policy_nullifier() {
+        let signals = Signals::new(&["age", "nullifier"]);
+        let public_inputs = [num_to_bytes(18), num_to_bytes(99)];
+
+        let mut policy = policy(vec![]);
+        assert_eq!(policy.nullifier(&public_inputs, &signals), Ok(None));
+
+        policy.nullifier_signal = "nullifier".to_string();
+        assert_eq!(
+            policy.nullifier(&public_inputs, &signals),
+            Ok(Some(num_to_bytes(99)))
+        );
+        assert_eq!(
+            policy.nullifier(&public_inputs[..1], &signals),
+            Err(AlbusError::InvalidPublicInputs.into())
+        );
+
+        policy.nullifier_signal = "unknown".to_string();
+        assert_eq!(
+            policy.nullifier(&public_inputs, &signals),
+            Err(AlbusError::InvalidNullifier.into())
+        );
+    }
+
+    #[test]
+    fn test_composite_aggregate() {
+        let mut composite = CompositeProofRequest {
+            service_provider: Default::default(),
+            composite_policy: Default::default(),
+            owner: Default::default(),
+            created_at: 0,
+            expired_at: 0,
+            verified_at: 0,
+            status: ProofRequestStatus::Pending,
+            bump: 0,
+            proof_requests: vec![],
         };
 
-        let signals = Signals::new(&["minAge", "maxAge", "countryLookup[2]"]);
-        let mut public_inputs = vec![[0; 32], [1; 32], [2; 32], [3; 32]];
+        let verified = [
+            proof_request(ProofRequestStatus::Verified, 20, 3),
+            proof_request(ProofRequestStatus::Verified, 0, 5),
+            proof_request(ProofRequestStatus::Verified, 10, 4),
+        ];
+        composite.aggregate(&verified);
+        assert!(composite.is_verified());
+        assert_eq!(composite.expired_at, 10);
+        assert_eq!(composite.verified_at, 5);
+
+        for (status, expected) in [
+            (ProofRequestStatus::Proved, ProofRequestStatus::Proved),
+            (ProofRequestStatus::Pending, ProofRequestStatus::Pending),
+            (ProofRequestStatus::Rejected, ProofRequestStatus::Rejected),
+        ] {
+            let mut components = verified.clone();
+            components[1].status = status;
+            composite.aggregate(&components);
+            assert!(composite.status == expected);
+            assert_eq!(composite.verified_at, 0);
+        }
+
+        let mut components = verified.clone();
+        components[0].status = ProofRequestStatus::Rejected;
+        components[2].status = ProofRequestStatus::Pending;
+        composite.aggregate(&components);
+        assert!(composite.status == ProofRequestStatus::Rejected);
+    }
 
-        policy.apply_rules(&mut public_inputs, &signals);
+    fn proof_request(
+        status: ProofRequestStatus,
+        expired_at: i64,
+        verified_at: i64,
+    ) -> ProofRequest {
+        ProofRequest {
+            service_provider: Default::default(),
+            policy: Default::default(),
+            circuit: Default::default(),
+            issuer: Default::default(),
+            owner: Default::default(),
+            identifier: 0,
+            created_at: 0,
+            expired_at,
+            verified_at,
+            proved_at: 0,
+            retention_end_date: 0,
+            status,
+            bump: 0,
+            proof: None,
+            public_inputs: vec![],
+            circuit_version: 0,
+            verified_slot: 0,
+        }
+    }
 
-        for (i, rule) in policy.rules.iter().enumerate() {
-            assert_eq!(public_inputs[i], rule.value);
+    fn rule(key: &str, operator: PolicyRuleOperator, value: u64) -> PolicyRule {
+        PolicyRule {
+            key: key.to_string(),
+            value: num_to_bytes(value),
+            label: "".to_string(),
+            operator,
         }
     }
+
+    #[test]
+    fn test_check_rules() {
+        let policy = policy(vec![
+            rule("minAge", PolicyRuleOperator::Eq, 18),
+            rule("age", PolicyRuleOperator::Gte, 18),
+            rule("birthYear", PolicyRuleOperator::Lte, 2005),
+            rule("country", PolicyRuleOperator::InSet, 1),
+            rule("country", PolicyRuleOperator::InSet, 2),
+            rule("countryLookup", PolicyRuleOperator::Eq, 7),
+            rule("countryLookup", PolicyRuleOperator::Eq, 8),
+            rule("root", PolicyRuleOperator::MerkleRoot, 99),
+            rule("root", PolicyRuleOperator::MerkleRoot, 100),
+        ]);
+
+        let signals = Signals::new(&[
+            "minAge",
+            "age",
+            "birthYear",
+            "country",
+            "countryLookup[2]",
+            "root",
+        ]);
+        let public_inputs: Vec<_> = [18, 30, 2000, 2, 7, 8, 99]
+            .into_iter()
+            .map(num_to_bytes)
+            .collect();
+
+        assert!(policy.check_rules(&public_inputs, &signals).is_ok());
+
+        // any of the accepted roots
+        let mut rotated = public_inputs.clone();
+        rotated[6] = num_to_bytes(100);
+        assert!(policy.check_rules(&rotated, &signals).is_ok());
+
+        for (index, value, err) in [
+            (0, 19, AlbusError::PolicyRuleNotEqual),
+            (1, 17, AlbusError::PolicyRuleTooLow),
+            (2, 2006, AlbusError::PolicyRuleTooHigh),
+            (3, 3, AlbusError::PolicyRuleNotInSet),
+            (5, 7, AlbusError::PolicyRuleNotEqual),
+            (6, 98, AlbusError::PolicyRuleInvalidMerkleRoot),
+            (6, 0, AlbusError::PolicyRuleInvalidMerkleRoot),
+        ] {
+            let mut inputs = public_inputs.clone();
+            inputs[index] = num_to_bytes(value);
+            assert_eq!(policy.check_rules(&inputs, &signals), Err(err.into()));
+        }
+
+        let signals = Signals::new(&["minAge"]);
+        assert_eq!(
+            policy.check_rules(&public_inputs, &signals),
+            Err(AlbusError::PolicyRuleSignalNotFound.into())
+        );
+    }
+
+    #[test]
+    fn test_legacy_policy_rule() {
+        let legacy = LegacyPolicyRule {
+            key: "minAge".to_string(),
+            value: num_to_bytes(18),
+            label: "label".to_string(),
+        };
+        let legacy_data = legacy.try_to_vec().unwrap();
+
+        // the operator is appended to the legacy layout
+        let rule = PolicyRule::from(legacy);
+        assert!(rule.operator == PolicyRuleOperator::Eq);
+        assert_eq!(
+            rule.try_to_vec().unwrap(),
+            [legacy_data.as_slice(), &[PolicyRuleOperator::Eq as u8]].concat()
+        );
+        assert_eq!(PolicyRule::INIT_SPACE, LegacyPolicyRule::INIT_SPACE + 1);
+    }
 }